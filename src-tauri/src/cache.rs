@@ -0,0 +1,479 @@
+use polars::prelude::*;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use crate::data_loader::{CsvOptions, Filter, RowGroupOffset, Sorting};
+use crate::jobs::Job;
+use crate::sql_params::SqlParameter;
+
+/// Default memory budget for collected views
+pub const DEFAULT_CACHE_BUDGET_MB: usize = 1024;
+
+/// Column that filtered and sorted Parquet views keep with the row number of
+/// each row in the file, to locate its row group. It is never shown.
+pub const ROW_INDEX: &str = "__row_index";
+
+/// Upper bound on the number of views, including paged views of files on disk
+/// that hold no data
+const MAX_ENTRIES: usize = 32;
+
+/// Identifies a cached view: a file with its filters and sorting, or a SQL
+/// query with the table names it was run against
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CacheKey {
+    pub file_path: Option<String>,
+    pub filters: Option<Vec<Filter>>,
+    pub sorting: Option<Vec<Sorting>>,
+    pub query: Option<String>,
+    pub table_names: Option<HashMap<String, String>>,
+    /// CSV options keyed by file path
+    pub csv_options: Option<HashMap<String, CsvOptions>>,
+    /// Parquet row group shown on its own
+    pub row_group: Option<usize>,
+    /// Window whose SQL session the query ran in
+    pub session: Option<String>,
+    /// Values bound to the named parameters of the query
+    pub parameters: Option<HashMap<String, SqlParameter>>,
+}
+
+/// Data behind a cached view. Sorted file views and SQL results are collected
+/// once; other file views are paged from the file on demand. A sliced Parquet
+/// scan uses the row counts of each row group to read only the row groups that
+/// overlap the requested page.
+#[derive(Clone)]
+pub enum CachedData {
+    Collected(DataFrame),
+    Paged {
+        lf: Box<LazyFrame>,
+        height: usize,
+        total_nulls: usize,
+    },
+}
+
+impl CachedData {
+    pub fn height(&self) -> usize {
+        match self {
+            CachedData::Collected(df) => df.height(),
+            CachedData::Paged { height, .. } => *height,
+        }
+    }
+
+    pub fn total_nulls(&self) -> usize {
+        match self {
+            CachedData::Collected(df) => df.get_columns().iter().map(|c| c.null_count()).sum(),
+            CachedData::Paged { total_nulls, .. } => *total_nulls,
+        }
+    }
+
+    pub fn page(&self, job: &Job, offset: usize, limit: usize) -> Result<DataFrame, String> {
+        match self {
+            CachedData::Collected(df) => Ok(without_row_index(df.slice(offset as i64, limit))),
+            CachedData::Paged { lf, .. } => job
+                .collect((**lf).clone().slice(offset as i64, limit as u32))
+                .map(without_row_index),
+        }
+    }
+
+    /// Full DataFrame for operations that need every row. Paged views are
+    /// collected on demand and not kept, so memory use stays bounded.
+    pub fn to_dataframe(&self, job: &Job) -> Result<DataFrame, String> {
+        match self {
+            CachedData::Collected(df) => Ok(without_row_index(df.clone())),
+            CachedData::Paged { lf, .. } => job.collect((**lf).clone()).map(without_row_index),
+        }
+    }
+
+    /// The view as a LazyFrame, for aggregations that should not collect
+    /// every row of a paged view
+    pub fn lazy(&self) -> LazyFrame {
+        let lf = match self {
+            CachedData::Collected(df) => df.clone().lazy(),
+            CachedData::Paged { lf, .. } => (**lf).clone(),
+        };
+        lf.drop(by_name([ROW_INDEX], false))
+    }
+
+    /// Row number in the file of a row of a filtered or sorted Parquet view
+    pub fn file_row(&self, job: &Job, row: usize) -> Result<usize, String> {
+        let (df, position) = match self {
+            CachedData::Collected(df) => (df.clone(), row),
+            CachedData::Paged { lf, .. } => (
+                job.collect((**lf).clone().slice(row as i64, 1).select([col(ROW_INDEX)]))?,
+                0,
+            ),
+        };
+
+        let index = df
+            .column(ROW_INDEX)
+            .and_then(|column| column.idx())
+            .map_err(|e| format!("Failed to read row index: {}", e))?
+            .get(position)
+            .ok_or_else(|| format!("Row {} not found", row))?;
+        Ok(index as usize)
+    }
+
+    /// Memory held by the view. Paged views hold their query plan, and the
+    /// data of any in-memory source it reads.
+    fn estimated_size(&self) -> usize {
+        match self {
+            CachedData::Collected(df) => df.estimated_size(),
+            CachedData::Paged { lf, .. } => plan_bytes(&lf.logical_plan),
+        }
+    }
+}
+
+fn without_row_index(df: DataFrame) -> DataFrame {
+    df.drop(ROW_INDEX).unwrap_or(df)
+}
+
+/// Memory held by the in-memory sources of a query plan, such as decoded CSV
+/// files or parsed JSON documents. Scans of files on disk hold nothing.
+fn plan_bytes(plan: &DslPlan) -> usize {
+    match plan {
+        DslPlan::DataFrameScan { df, .. } => df.estimated_size(),
+        DslPlan::Scan {
+            sources: ScanSources::Buffers(buffers),
+            ..
+        } => buffers.iter().map(|buffer| buffer.len()).sum(),
+        DslPlan::Filter { input, .. }
+        | DslPlan::Cache { input, .. }
+        | DslPlan::Select { input, .. }
+        | DslPlan::GroupBy { input, .. }
+        | DslPlan::HStack { input, .. }
+        | DslPlan::MatchToSchema { input, .. }
+        | DslPlan::Distinct { input, .. }
+        | DslPlan::Sort { input, .. }
+        | DslPlan::Slice { input, .. }
+        | DslPlan::MapFunction { input, .. } => plan_bytes(input),
+        DslPlan::Join {
+            input_left,
+            input_right,
+            ..
+        } => plan_bytes(input_left) + plan_bytes(input_right),
+        DslPlan::Union { inputs, .. } | DslPlan::HConcat { inputs, .. } => {
+            inputs.iter().map(plan_bytes).sum()
+        }
+        _ => 0,
+    }
+}
+
+struct CacheEntry {
+    key: CacheKey,
+    /// Files the view reads, used to invalidate it when one of them changes
+    sources: Vec<String>,
+    data: CachedData,
+    size: usize,
+}
+
+#[derive(Serialize)]
+pub struct CacheUsage {
+    pub entries: Vec<CacheEntryUsage>,
+    pub used_bytes: usize,
+    pub budget_bytes: usize,
+}
+
+#[derive(Serialize)]
+pub struct CacheEntryUsage {
+    pub file_path: Option<String>,
+    pub query: Option<String>,
+    pub filtered: bool,
+    pub sorted: bool,
+    pub collected: bool,
+    pub rows: usize,
+    pub bytes: usize,
+}
+
+/// Least-recently-used cache of views, bounded by the memory they hold
+pub struct ViewCache {
+    /// Most recently used first
+    entries: VecDeque<CacheEntry>,
+    budget_bytes: usize,
+    /// Row group offsets of Parquet paths, so paging through a row group or
+    /// locating a row does not read the footers again
+    row_groups: HashMap<String, Arc<Vec<RowGroupOffset>>>,
+}
+
+impl Default for ViewCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_BUDGET_MB * 1024 * 1024)
+    }
+}
+
+impl ViewCache {
+    pub fn new(budget_bytes: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            budget_bytes,
+            row_groups: HashMap::new(),
+        }
+    }
+
+    /// Look up a view and mark it as most recently used
+    pub fn get(&mut self, key: &CacheKey) -> Option<CachedData> {
+        let idx = self.entries.iter().position(|entry| &entry.key == key)?;
+        let entry = self.entries.remove(idx)?;
+        let data = entry.data.clone();
+        self.entries.push_front(entry);
+        Some(data)
+    }
+
+    pub fn insert(&mut self, key: CacheKey, sources: Vec<String>, data: CachedData) {
+        self.entries.retain(|entry| entry.key != key);
+
+        let size = data.estimated_size();
+        self.entries.push_front(CacheEntry {
+            key,
+            sources,
+            data,
+            size,
+        });
+
+        self.evict_to_budget();
+    }
+
+    pub fn row_group_offsets(&self, file_path: &str) -> Option<Arc<Vec<RowGroupOffset>>> {
+        self.row_groups.get(file_path).cloned()
+    }
+
+    pub fn insert_row_group_offsets(&mut self, file_path: &str, offsets: Arc<Vec<RowGroupOffset>>) {
+        self.row_groups.insert(file_path.to_string(), offsets);
+    }
+
+    /// Drop every view that reads the given file, and its row group offsets
+    pub fn evict_file(&mut self, file_path: &str) -> usize {
+        self.row_groups.remove(file_path);
+        let before = self.entries.len();
+        self.entries
+            .retain(|entry| !entry.sources.iter().any(|source| source == file_path));
+        before - self.entries.len()
+    }
+
+    /// Drop every view of a SQL query, after the views or tables of a SQL
+    /// session changed
+    pub fn evict_queries(&mut self) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|entry| entry.key.query.is_none());
+        before - self.entries.len()
+    }
+
+    pub fn set_budget(&mut self, budget_bytes: usize) {
+        self.budget_bytes = budget_bytes;
+        self.evict_to_budget();
+    }
+
+    pub fn usage(&self) -> CacheUsage {
+        CacheUsage {
+            entries: self
+                .entries
+                .iter()
+                .map(|entry| CacheEntryUsage {
+                    file_path: entry.key.file_path.clone(),
+                    query: entry.key.query.clone(),
+                    filtered: entry.key.filters.as_ref().is_some_and(|f| !f.is_empty()),
+                    sorted: entry.key.sorting.as_ref().is_some_and(|s| !s.is_empty()),
+                    collected: matches!(entry.data, CachedData::Collected(_)),
+                    rows: entry.data.height(),
+                    bytes: entry.size,
+                })
+                .collect(),
+            used_bytes: self.used_bytes(),
+            budget_bytes: self.budget_bytes,
+        }
+    }
+
+    pub fn budget_bytes(&self) -> usize {
+        self.budget_bytes
+    }
+
+    fn used_bytes(&self) -> usize {
+        self.entries.iter().map(|entry| entry.size).sum()
+    }
+
+    /// Evict least recently used views until the budget is met. The most
+    /// recently used view is always kept, even if it alone exceeds the budget.
+    fn evict_to_budget(&mut self) {
+        self.entries.truncate(MAX_ENTRIES);
+
+        while self.used_bytes() > self.budget_bytes {
+            // Paged views of files on disk hold no data, so evicting them would not free anything
+            match self.entries.iter().rposition(|entry| entry.size > 0) {
+                Some(idx) if idx > 0 => {
+                    self.entries.remove(idx);
+                }
+                _ => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_loader::test_utils::{path_str, temp_dir, write_parquet};
+
+    fn file_key(file_path: &str) -> CacheKey {
+        CacheKey {
+            file_path: Some(file_path.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn paged(lf: LazyFrame) -> CachedData {
+        CachedData::Paged {
+            lf: Box::new(lf),
+            height: 0,
+            total_nulls: 0,
+        }
+    }
+
+    fn collected(rows: i64) -> CachedData {
+        CachedData::Collected(df!("a" => (0..rows).collect::<Vec<_>>()).unwrap())
+    }
+
+    fn cached_paths(cache: &ViewCache) -> Vec<String> {
+        cache
+            .usage()
+            .entries
+            .into_iter()
+            .map(|entry| entry.file_path.or(entry.query).unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn least_recently_used_views_are_evicted_first() {
+        let size = collected(1000).estimated_size();
+        let mut cache = ViewCache::new(2 * size);
+        cache.insert(file_key("a"), vec![], collected(1000));
+        cache.insert(file_key("b"), vec![], collected(1000));
+        assert!(cache.get(&file_key("a")).is_some());
+
+        cache.insert(file_key("c"), vec![], collected(1000));
+        assert_eq!(cached_paths(&cache), vec!["c", "a"]);
+
+        // The most recently used view is kept even if it alone exceeds the budget
+        cache.insert(file_key("big"), vec![], collected(10_000));
+        assert_eq!(cached_paths(&cache), vec!["big"]);
+
+        cache.set_budget(0);
+        assert_eq!(cached_paths(&cache), vec!["big"]);
+    }
+
+    #[test]
+    fn views_are_evicted_by_source_and_query() {
+        let mut cache = ViewCache::default();
+        cache.insert(file_key("a"), vec!["a.csv".to_string()], collected(1));
+        cache.insert(
+            file_key("b"),
+            vec!["a.csv".to_string(), "b.csv".to_string()],
+            collected(1),
+        );
+        let query = CacheKey {
+            query: Some("SELECT 1".to_string()),
+            ..Default::default()
+        };
+        cache.insert(query.clone(), vec!["b.csv".to_string()], collected(1));
+        assert!(cache.get(&query).is_some());
+
+        assert_eq!(cache.evict_file("a.csv"), 2);
+        assert_eq!(cached_paths(&cache), vec!["SELECT 1"]);
+        assert_eq!(cache.evict_queries(), 1);
+        assert!(cache.get(&query).is_none());
+    }
+
+    #[test]
+    fn query_views_are_told_apart_by_their_bindings() {
+        let key = |n: i64| CacheKey {
+            query: Some("SELECT * FROM t WHERE a < :n".to_string()),
+            session: Some("main".to_string()),
+            parameters: Some(HashMap::from([("n".to_string(), SqlParameter::Integer(n))])),
+            ..Default::default()
+        };
+        let mut cache = ViewCache::default();
+        cache.insert(key(2), vec![], collected(2));
+        cache.insert(key(5), vec![], collected(5));
+
+        let height = |data: Option<CachedData>| match data {
+            Some(CachedData::Collected(df)) => Some(df.height()),
+            _ => None,
+        };
+        assert_eq!(height(cache.get(&key(2))), Some(2));
+        assert_eq!(height(cache.get(&key(5))), Some(5));
+        assert_eq!(height(cache.get(&key(3))), None);
+
+        // The same query and bindings in another window is another view
+        let other_window = CacheKey {
+            session: Some("other".to_string()),
+            ..key(2)
+        };
+        assert_eq!(height(cache.get(&other_window)), None);
+    }
+
+    #[test]
+    fn the_number_of_views_is_bounded() {
+        let mut cache = ViewCache::default();
+        for i in 0..MAX_ENTRIES + 5 {
+            cache.insert(file_key(&i.to_string()), vec![], collected(1));
+        }
+        assert_eq!(cache.usage().entries.len(), MAX_ENTRIES);
+        assert!(cache.get(&file_key("0")).is_none());
+        assert!(cache
+            .get(&file_key(&(MAX_ENTRIES + 4).to_string()))
+            .is_some());
+    }
+
+    #[test]
+    fn paged_views_count_their_in_memory_sources() {
+        let mut df = df!("a" => (0..1000i64).collect::<Vec<_>>()).unwrap();
+        let size = df.estimated_size();
+
+        let dir = temp_dir("cache-paged");
+        let file = dir.join("a.parquet");
+        write_parquet(&file, &mut df, 100);
+        let scan =
+            LazyFrame::scan_parquet(PlPath::new(path_str(&file)), ScanArgsParquet::default())
+                .unwrap();
+
+        let mut cache = ViewCache::new(size);
+        cache.insert(
+            file_key("file"),
+            vec![],
+            paged(scan.filter(col("a").gt(lit(10)))),
+        );
+        cache.insert(
+            file_key("memory"),
+            vec![],
+            paged(df.clone().lazy().slice(0, 10)),
+        );
+
+        let usage = cache.usage();
+        let bytes: Vec<_> = usage.entries.iter().map(|entry| entry.bytes).collect();
+        assert_eq!(bytes, vec![size, 0]);
+
+        // A second in-memory view goes over the budget and evicts the first
+        cache.insert(file_key("other"), vec![], paged(df.lazy()));
+        assert!(cache.get(&file_key("memory")).is_none());
+        assert!(cache.get(&file_key("file")).is_some());
+    }
+
+    #[test]
+    fn lazy_views_hide_the_row_index() {
+        let df = df!("a" => [3i64, 1, 2])
+            .unwrap()
+            .lazy()
+            .with_row_index(ROW_INDEX, None)
+            .sort(["a"], Default::default())
+            .collect()
+            .unwrap();
+        let mut lf = CachedData::Collected(df).lazy();
+        let names: Vec<_> = lf.collect_schema().unwrap().iter_names().cloned().collect();
+        assert_eq!(names, vec!["a"]);
+
+        // Views without a row index are left as they are
+        let df = paged(df!("a" => [1i64]).unwrap().lazy())
+            .lazy()
+            .collect()
+            .unwrap();
+        assert_eq!(df.width(), 1);
+    }
+}
@@ -0,0 +1,292 @@
+use polars::prelude::*;
+use serde::Serialize;
+
+use super::dataframe_processor::is_numeric;
+use super::histogram::{bin_edges, bin_index, Binning};
+
+/// Bins along each axis of a pair density when none is given
+const DEFAULT_DENSITY_BINS: usize = 20;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CorrelationMethod {
+    /// Linear relationship between the values
+    Pearson,
+    /// Monotonic relationship, from the ranks of the values
+    Spearman,
+}
+
+/// Correlation matrix of the numeric columns of a DataFrame
+#[derive(Serialize)]
+pub struct CorrelationMatrix {
+    pub columns: Vec<String>,
+    /// Row-major coefficients. `None` where a pair has fewer than two rows with
+    /// both values present, or one of the columns is constant.
+    pub values: Vec<Vec<Option<f64>>>,
+}
+
+/// Counts of rows in a grid of bins over two numeric columns
+#[derive(Serialize)]
+pub struct PairDensity {
+    pub x_column: String,
+    pub y_column: String,
+    /// Bin edges along each axis, one more than the bins
+    pub x_bins: Vec<f64>,
+    pub y_bins: Vec<f64>,
+    /// Counts indexed by y bin, then x bin
+    pub counts: Vec<Vec<usize>>,
+    /// Rows where both values are present
+    pub rows: usize,
+}
+
+/// Only the numeric columns of a LazyFrame, so other columns are not read
+pub fn select_numeric(mut lf: LazyFrame) -> Result<LazyFrame, String> {
+    let schema = lf
+        .collect_schema()
+        .map_err(|e| format!("Failed to read schema: {}", e))?;
+    let columns: Vec<Expr> = schema
+        .iter()
+        .filter(|(_, dtype)| is_numeric(dtype))
+        .map(|(name, _)| col(name.clone()))
+        .collect();
+
+    Ok(lf.select(columns))
+}
+
+/// Correlation between every pair of numeric columns, using the rows where
+/// both values are present
+pub fn calculate_correlations(
+    df: &DataFrame,
+    method: CorrelationMethod,
+) -> Result<CorrelationMatrix, String> {
+    let mut columns = Vec::new();
+    let mut data = Vec::new();
+
+    for series in df.get_columns() {
+        if !is_numeric(series.dtype()) {
+            continue;
+        }
+        columns.push(series.name().to_string());
+        data.push(finite_values(series.as_materialized_series())?);
+    }
+
+    // Each column is ranked once. Ranks only carry over to a pair if it keeps
+    // every present value of both columns; otherwise the pair is ranked again.
+    let ranked: Vec<Vec<Option<f64>>> = match method {
+        CorrelationMethod::Pearson => Vec::new(),
+        CorrelationMethod::Spearman => data.iter().map(|values| column_ranks(values)).collect(),
+    };
+    let present: Vec<usize> = data
+        .iter()
+        .map(|values| values.iter().flatten().count())
+        .collect();
+
+    let n = columns.len();
+    let mut values = vec![vec![None; n]; n];
+    for i in 0..n {
+        for j in i..n {
+            let pairs = complete_pairs(&data[i], &data[j]);
+            let r = match method {
+                CorrelationMethod::Pearson => pearson(&pairs),
+                CorrelationMethod::Spearman
+                    if pairs.len() == present[i] && pairs.len() == present[j] =>
+                {
+                    pearson(&complete_pairs(&ranked[i], &ranked[j]))
+                }
+                CorrelationMethod::Spearman => spearman(&pairs),
+            };
+            values[i][j] = r;
+            values[j][i] = r;
+        }
+    }
+
+    Ok(CorrelationMatrix { columns, values })
+}
+
+/// Equal-width 2-D histogram of two numeric columns, for a heatmap or a
+/// scatter plot of many rows
+pub fn calculate_pair_density(
+    df: &DataFrame,
+    x_column: &str,
+    y_column: &str,
+    num_bins: Option<usize>,
+) -> Result<PairDensity, String> {
+    let num_bins = num_bins.unwrap_or(DEFAULT_DENSITY_BINS);
+    if num_bins == 0 {
+        return Err("Number of bins must be positive".to_string());
+    }
+
+    let mut axes = Vec::new();
+    for name in [x_column, y_column] {
+        let column = df
+            .column(name)
+            .map_err(|e| format!("Column not found: {}", e))?;
+        if !is_numeric(column.dtype()) {
+            return Err(format!("Column is not numeric: {}", name));
+        }
+        axes.push(finite_values(column.as_materialized_series())?);
+    }
+
+    let pairs = complete_pairs(&axes[0], &axes[1]);
+    if pairs.is_empty() {
+        return Err("No rows with both values present".to_string());
+    }
+
+    let edges = |values: Vec<f64>| {
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        bin_edges(&Binning::EqualWidth, &values, min, max, Some(num_bins))
+    };
+    let x_bins = edges(pairs.iter().map(|(x, _)| *x).collect());
+    let y_bins = edges(pairs.iter().map(|(_, y)| *y).collect());
+
+    let mut counts = vec![vec![0usize; x_bins.len() - 1]; y_bins.len() - 1];
+    for (x, y) in &pairs {
+        if let (Some(i), Some(j)) = (bin_index(&x_bins, *x), bin_index(&y_bins, *y)) {
+            counts[j][i] += 1;
+        }
+    }
+
+    Ok(PairDensity {
+        x_column: x_column.to_string(),
+        y_column: y_column.to_string(),
+        x_bins,
+        y_bins,
+        counts,
+        rows: pairs.len(),
+    })
+}
+
+/// Values of a numeric column, with NaN and infinite values treated as missing
+fn finite_values(series: &Series) -> Result<Vec<Option<f64>>, String> {
+    let values = series
+        .cast(&DataType::Float64)
+        .map_err(|e| format!("Failed to convert to numeric: {}", e))?;
+    let values = values
+        .f64()
+        .map_err(|e| format!("Failed to get f64 series: {}", e))?
+        .into_iter()
+        .map(|v| v.filter(|v| v.is_finite()))
+        .collect();
+
+    Ok(values)
+}
+
+/// Pairs of values from rows where both are present
+fn complete_pairs(x: &[Option<f64>], y: &[Option<f64>]) -> Vec<(f64, f64)> {
+    x.iter()
+        .zip(y)
+        .filter_map(|(x, y)| Some(((*x)?, (*y)?)))
+        .collect()
+}
+
+fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 2 {
+        return None;
+    }
+
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let mut cov = 0.0;
+    let mut var_x = 0.0;
+    let mut var_y = 0.0;
+    for (x, y) in pairs {
+        cov += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+        var_y += (y - mean_y).powi(2);
+    }
+
+    if var_x == 0.0 || var_y == 0.0 {
+        return None;
+    }
+    Some((cov / (var_x.sqrt() * var_y.sqrt())).clamp(-1.0, 1.0))
+}
+
+/// Pearson correlation of the ranks of the values
+fn spearman(pairs: &[(f64, f64)]) -> Option<f64> {
+    let x_ranks = ranks(pairs.iter().map(|(x, _)| *x).collect());
+    let y_ranks = ranks(pairs.iter().map(|(_, y)| *y).collect());
+    let ranked: Vec<(f64, f64)> = x_ranks.into_iter().zip(y_ranks).collect();
+    pearson(&ranked)
+}
+
+/// Ranks of the present values of a column, by row
+fn column_ranks(values: &[Option<f64>]) -> Vec<Option<f64>> {
+    let mut ranks = ranks(values.iter().flatten().copied().collect()).into_iter();
+    values
+        .iter()
+        .map(|value| value.and_then(|_| ranks.next()))
+        .collect()
+}
+
+/// Rank of each value starting at 1, with tied values sharing their average
+/// rank
+fn ranks(values: Vec<f64>) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        // Positions start..end hold ranks start + 1 ..= end
+        let rank = (start + end + 1) as f64 / 2.0;
+        for &idx in &order[start..end] {
+            ranks[idx] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tied_values_share_their_average_rank() {
+        assert_eq!(ranks(vec![3.0, 1.0, 3.0, 2.0]), vec![3.5, 1.0, 3.5, 2.0]);
+        assert_eq!(
+            column_ranks(&[Some(2.0), None, Some(1.0)]),
+            vec![Some(2.0), None, Some(1.0)]
+        );
+    }
+
+    #[test]
+    fn spearman_ranks_pairs_with_missing_rows_again() {
+        let df = df!(
+            "x" => [1.0, 2.0, 3.0, 4.0, 5.0],
+            "y" => [Some(5.0), Some(1.0), Some(4.0), Some(2.0), Some(3.0)],
+            "z" => [Some(2.0), None, Some(1.0), Some(5.0), Some(4.0)],
+            "s" => ["a", "b", "c", "d", "e"],
+        )
+        .unwrap();
+        let matrix = calculate_correlations(&df, CorrelationMethod::Spearman).unwrap();
+        assert_eq!(matrix.columns, vec!["x", "y", "z"]);
+
+        let column = |name: &str| finite_values(df.column(name).unwrap().as_materialized_series());
+        let (x, y, z) = (
+            column("x").unwrap(),
+            column("y").unwrap(),
+            column("z").unwrap(),
+        );
+        assert_eq!(matrix.values[0][1], spearman(&complete_pairs(&x, &y)));
+        assert_eq!(matrix.values[0][2], spearman(&complete_pairs(&x, &z)));
+        assert_eq!(matrix.values[1][2], spearman(&complete_pairs(&y, &z)));
+        let close = |r: Option<f64>, expected: f64| (r.unwrap() - expected).abs() < 1e-12;
+        assert!(close(matrix.values[0][0], 1.0));
+        assert!(close(matrix.values[0][1], -0.3));
+    }
+
+    #[test]
+    fn constant_columns_have_no_correlation() {
+        let df = df!("a" => [1.0, 2.0, 3.0], "b" => [7.0, 7.0, 7.0]).unwrap();
+        let matrix = calculate_correlations(&df, CorrelationMethod::Pearson).unwrap();
+        assert_eq!(matrix.values[0][1], None);
+        assert_eq!(matrix.values[1][1], None);
+    }
+}
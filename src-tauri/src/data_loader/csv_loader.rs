@@ -1,10 +1,266 @@
+use polars::polars_utils::mmap::MemSlice;
 use polars::prelude::*;
+use serde::{Deserialize, Serialize};
 
-/// Opens a CSV file and returns a LazyFrame
-pub fn open_csv(file_path: &str) -> Result<LazyFrame, String> {
-    LazyCsvReader::new(PlPath::from_str(file_path))
-        .with_infer_schema_length(Some(10000))
-        .with_has_header(true)
+use super::format_registry::scan_path;
+
+/// CSV dialect options. The defaults match a standard comma-separated file
+/// with a header row.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CsvOptions {
+    pub delimiter: char,
+    /// `None` disables quoting
+    pub quote_char: Option<char>,
+    pub has_header: bool,
+    pub encoding: CsvFileEncoding,
+    /// Strings that are read as null in every column
+    pub null_values: Vec<String>,
+    /// Lines skipped before the header (or the first row if there is no header)
+    pub skip_rows: usize,
+    /// Rows used to infer the schema; `None` scans the whole file
+    pub infer_schema_length: Option<usize>,
+    pub try_parse_dates: bool,
+    /// Parse `1,5` as 1.5, for files that use a decimal comma
+    pub decimal_comma: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvFileEncoding {
+    #[default]
+    Utf8,
+    /// UTF-8 with invalid bytes replaced by `�`
+    LossyUtf8,
+    /// ISO-8859-1
+    Latin1,
+    /// Windows code page 1252, used by Excel exports in Western Europe. A
+    /// superset of Latin-1 with `€`, curly quotes and dashes in 0x80-0x9F.
+    Windows1252,
+}
+
+/// Characters of the Windows-1252 bytes 0x80-0x9F. The five unassigned bytes
+/// map to the C1 control characters of the same value, as in Latin-1.
+const WINDOWS_1252_C1: [char; 32] = [
+    '\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}', '\u{8F}',
+    '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{2DC}', '\u{2122}', '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}', '\u{178}',
+];
+
+impl CsvFileEncoding {
+    /// Polars parses UTF-8 itself; the single-byte encodings are decoded first
+    fn is_utf8(self) -> bool {
+        matches!(self, CsvFileEncoding::Utf8 | CsvFileEncoding::LossyUtf8)
+    }
+
+    /// Decode Latin-1 or Windows-1252 text. Every byte is a character in both.
+    fn decode(self, bytes: &[u8]) -> String {
+        bytes
+            .iter()
+            .map(|&b| match (self, b) {
+                (CsvFileEncoding::Windows1252, 0x80..=0x9F) => WINDOWS_1252_C1[(b - 0x80) as usize],
+                _ => b as char,
+            })
+            .collect()
+    }
+}
+
+impl CsvOptions {
+    /// Default options for a file, using a tab delimiter for `.tsv` files
+    pub fn for_path(file_path: &str) -> Self {
+        let is_tsv = std::path::Path::new(file_path)
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("tsv"));
+
+        CsvOptions {
+            delimiter: if is_tsv { '\t' } else { ',' },
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            quote_char: Some('"'),
+            has_header: true,
+            encoding: CsvFileEncoding::Utf8,
+            null_values: Vec::new(),
+            skip_rows: 0,
+            infer_schema_length: Some(10000),
+            try_parse_dates: false,
+            decimal_comma: false,
+        }
+    }
+}
+
+/// Opens a CSV file and returns a LazyFrame. Without options the file is
+/// read with the defaults for its extension.
+pub fn open_csv(file_path: &str, options: Option<&CsvOptions>) -> Result<LazyFrame, String> {
+    let default_options;
+    let options = match options {
+        Some(options) => options,
+        None => {
+            default_options = CsvOptions::for_path(file_path);
+            &default_options
+        }
+    };
+
+    let delimiter = ascii_byte(options.delimiter, "delimiter")?;
+    let quote_char = options
+        .quote_char
+        .map(|c| ascii_byte(c, "quote character"))
+        .transpose()?;
+
+    if options.decimal_comma && delimiter == b',' {
+        return Err("A decimal comma cannot be used with a comma delimiter".to_string());
+    }
+
+    let null_values = if options.null_values.is_empty() {
+        None
+    } else {
+        Some(NullValues::AllColumns(
+            options
+                .null_values
+                .iter()
+                .map(|v| v.as_str().into())
+                .collect(),
+        ))
+    };
+
+    let encoding = match options.encoding {
+        CsvFileEncoding::LossyUtf8 => CsvEncoding::LossyUtf8,
+        _ => CsvEncoding::Utf8,
+    };
+
+    // Polars only parses UTF-8, so other encodings are decoded in memory first
+    let reader = if options.encoding.is_utf8() {
+        LazyCsvReader::new(scan_path(file_path))
+    } else {
+        let bytes = std::fs::read(file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+        let text = options.encoding.decode(&bytes);
+        let buffer = MemSlice::from_vec(text.into_bytes());
+        LazyCsvReader::new_with_sources(ScanSources::Buffers([buffer].into()))
+    };
+
+    reader
+        .with_separator(delimiter)
+        .with_quote_char(quote_char)
+        .with_has_header(options.has_header)
+        .with_encoding(encoding)
+        .with_null_values(null_values)
+        .with_skip_rows(options.skip_rows)
+        .with_infer_schema_length(options.infer_schema_length)
+        .with_try_parse_dates(options.try_parse_dates)
+        .with_decimal_comma(options.decimal_comma)
         .finish()
         .map_err(|e| format!("Failed to scan CSV file: {}", e))
 }
+
+fn ascii_byte(c: char, what: &str) -> Result<u8, String> {
+    if c.is_ascii() {
+        Ok(c as u8)
+    } else {
+        Err(format!(
+            "CSV {} must be a single ASCII character, got '{}'",
+            what, c
+        ))
+    }
+}
+
+/// CSV has no magic bytes, so accept text whose first line contains a delimiter
+pub fn sniff_csv(bytes: &[u8]) -> bool {
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text,
+        // A multi-byte character cut off at the end of the sample is fine
+        Err(e) if e.error_len().is_none() => {
+            std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return false,
+    };
+
+    if text.contains('\0') {
+        return false;
+    }
+
+    text.lines()
+        .next()
+        .map(|line| line.contains([',', ';', '\t', '|']))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_loader::test_utils::{path_str, temp_dir};
+
+    fn read(path: &std::path::Path, options: &CsvOptions) -> DataFrame {
+        open_csv(path_str(path), Some(options))
+            .unwrap()
+            .collect()
+            .unwrap()
+    }
+
+    #[test]
+    fn decodes_single_byte_encodings() {
+        let dir = temp_dir("csv-encodings");
+        let path = dir.join("export.csv");
+        // "Café;€ 1,5" in Windows-1252; 0xE9 is é in both encodings, 0x80 is €
+        // only in Windows-1252
+        std::fs::write(&path, b"name;price\nCaf\xe9;\x80 1,5\n").unwrap();
+
+        let mut options = CsvOptions {
+            delimiter: ';',
+            encoding: CsvFileEncoding::Windows1252,
+            ..Default::default()
+        };
+        let df = read(&path, &options);
+        let name = df.column("name").unwrap().str().unwrap().get(0);
+        let price = df.column("price").unwrap().str().unwrap().get(0);
+        assert_eq!(name, Some("Café"));
+        assert_eq!(price, Some("€ 1,5"));
+
+        options.encoding = CsvFileEncoding::Latin1;
+        let df = read(&path, &options);
+        let price = df.column("price").unwrap().str().unwrap().get(0);
+        assert_eq!(price, Some("\u{80} 1,5"));
+
+        options.encoding = CsvFileEncoding::LossyUtf8;
+        let df = read(&path, &options);
+        let name = df.column("name").unwrap().str().unwrap().get(0);
+        assert_eq!(name, Some("Caf\u{FFFD}"));
+    }
+
+    #[test]
+    fn windows_1252_table_matches_code_points() {
+        let text = CsvFileEncoding::Windows1252.decode(&[0x80, 0x8A, 0x93, 0x94, 0x96, 0x9F, 0xFF]);
+        assert_eq!(text, "€Š\u{201C}\u{201D}–Ÿÿ");
+    }
+
+    #[test]
+    fn semicolon_file_with_decimal_comma() {
+        let dir = temp_dir("csv-dialect");
+        let path = dir.join("prices.csv");
+        std::fs::write(&path, "# exported\nitem;price\na;1,5\nb;NA\n").unwrap();
+
+        let options = CsvOptions {
+            delimiter: ';',
+            decimal_comma: true,
+            skip_rows: 1,
+            null_values: vec!["NA".to_string()],
+            ..Default::default()
+        };
+        let df = read(&path, &options);
+        let price = df.column("price").unwrap().f64().unwrap();
+        assert_eq!(price.get(0), Some(1.5));
+        assert_eq!(price.get(1), None);
+
+        let comma = CsvOptions {
+            decimal_comma: true,
+            ..Default::default()
+        };
+        assert!(open_csv(path_str(&path), Some(&comma)).is_err());
+    }
+}
@@ -24,6 +24,257 @@ pub fn apply_sorts(lf: LazyFrame, sorts: Vec<Sorting>) -> Result<LazyFrame, Stri
     Ok(result)
 }
 
+/// Comparison operator of a filter condition
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+/// Row filter. Values are cast to the type of the column they are compared
+/// with, so dates and timestamps can be given as strings.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Filter {
+    And {
+        filters: Vec<Filter>,
+    },
+    Or {
+        filters: Vec<Filter>,
+    },
+    Compare {
+        column: String,
+        op: CompareOp,
+        value: serde_json::Value,
+    },
+    /// Substring match on the string representation of the column
+    Contains {
+        column: String,
+        value: String,
+        #[serde(default, rename = "caseSensitive")]
+        case_sensitive: bool,
+    },
+    Regex {
+        column: String,
+        pattern: String,
+    },
+    IsNull {
+        column: String,
+    },
+    IsNotNull {
+        column: String,
+    },
+    In {
+        column: String,
+        values: Vec<serde_json::Value>,
+    },
+    /// Inclusive range
+    Between {
+        column: String,
+        low: serde_json::Value,
+        high: serde_json::Value,
+    },
+}
+
+/// Apply filters to a LazyFrame. A row is kept if it matches every filter.
+pub fn apply_filters(mut lf: LazyFrame, filters: Vec<Filter>) -> Result<LazyFrame, String> {
+    if filters.is_empty() {
+        return Ok(lf);
+    }
+
+    let schema = lf
+        .collect_schema()
+        .map_err(|e| format!("Failed to read schema: {}", e))?;
+
+    let mut predicate = lit(true);
+    for filter in &filters {
+        predicate = predicate.and(filter_expr(filter, &schema)?);
+    }
+
+    Ok(lf.filter(predicate))
+}
+
+fn filter_expr(filter: &Filter, schema: &Schema) -> Result<Expr, String> {
+    let expr = match filter {
+        Filter::And { filters } => {
+            let mut expr = lit(true);
+            for filter in filters {
+                expr = expr.and(filter_expr(filter, schema)?);
+            }
+            expr
+        }
+        Filter::Or { filters } => {
+            let mut expr = lit(false);
+            for filter in filters {
+                expr = expr.or(filter_expr(filter, schema)?);
+            }
+            expr
+        }
+        Filter::Compare { column, op, value } => {
+            let dtype = column_dtype(schema, column)?;
+            if matches!(op, CompareOp::Eq | CompareOp::NotEq) {
+                check_equality(column, value, dtype)?;
+            }
+            let column = compared_column(column, value, dtype);
+            let value = filter_literal(value, dtype)?;
+            match op {
+                CompareOp::Eq => column.eq(value),
+                CompareOp::NotEq => column.neq(value),
+                CompareOp::Lt => column.lt(value),
+                CompareOp::LtEq => column.lt_eq(value),
+                CompareOp::Gt => column.gt(value),
+                CompareOp::GtEq => column.gt_eq(value),
+            }
+        }
+        Filter::Contains {
+            column,
+            value,
+            case_sensitive,
+        } => {
+            column_dtype(schema, column)?;
+            let text = col(column.as_str()).cast(DataType::String);
+            if *case_sensitive {
+                text.str().contains_literal(lit(value.clone()))
+            } else {
+                text.str()
+                    .to_lowercase()
+                    .str()
+                    .contains_literal(lit(value.to_lowercase()))
+            }
+        }
+        Filter::Regex { column, pattern } => {
+            column_dtype(schema, column)?;
+            col(column.as_str())
+                .cast(DataType::String)
+                .str()
+                .contains(lit(pattern.clone()), true)
+        }
+        Filter::IsNull { column } => {
+            column_dtype(schema, column)?;
+            col(column.as_str()).is_null()
+        }
+        Filter::IsNotNull { column } => {
+            column_dtype(schema, column)?;
+            col(column.as_str()).is_not_null()
+        }
+        Filter::In { column, values } => {
+            let dtype = column_dtype(schema, column)?;
+            let mut expr = lit(false);
+            for value in values {
+                check_equality(column, value, dtype)?;
+                expr = expr.or(col(column.as_str()).eq(filter_literal(value, dtype)?));
+            }
+            expr
+        }
+        Filter::Between { column, low, high } => {
+            let dtype = column_dtype(schema, column)?;
+            compared_column(column, low, dtype)
+                .gt_eq(filter_literal(low, dtype)?)
+                .and(compared_column(column, high, dtype).lt_eq(filter_literal(high, dtype)?))
+        }
+    };
+
+    Ok(expr)
+}
+
+pub(super) fn column_dtype<'a>(schema: &'a Schema, column: &str) -> Result<&'a DataType, String> {
+    schema
+        .get(column)
+        .ok_or_else(|| format!("Column not found: {}", column))
+}
+
+/// Fractional number compared with an integer column. Casting it to the type
+/// of the column would truncate it, so `x < 1.5` would act as `x < 1`.
+fn fractional_for_integer(value: &serde_json::Value, dtype: &DataType) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(n) if dtype.is_integer() => n
+            .as_f64()
+            .filter(|f| !n.is_i64() && !n.is_u64() && f.fract() != 0.0),
+        _ => None,
+    }
+}
+
+/// Column to compare with a filter value. Integer columns are compared with
+/// fractional numbers as Float64.
+fn compared_column(column: &str, value: &serde_json::Value, dtype: &DataType) -> Expr {
+    match fractional_for_integer(value, dtype) {
+        Some(_) => col(column).cast(DataType::Float64),
+        None => col(column),
+    }
+}
+
+/// Reject equality with a fractional number on an integer column, which no
+/// row could match
+pub(super) fn check_equality(
+    column: &str,
+    value: &serde_json::Value,
+    dtype: &DataType,
+) -> Result<(), String> {
+    match fractional_for_integer(value, dtype) {
+        Some(f) => Err(format!(
+            "Column {} holds integers and can't be compared for equality with {}",
+            column, f
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Literal for a filter value, cast to the type of the column. Fractional
+/// numbers compared with an integer column stay Float64.
+pub(super) fn filter_literal(value: &serde_json::Value, dtype: &DataType) -> Result<Expr, String> {
+    if let Some(f) = fractional_for_integer(value, dtype) {
+        return Ok(lit(f));
+    }
+
+    let value = match value {
+        serde_json::Value::Null => lit(NULL),
+        serde_json::Value::Bool(b) => lit(*b),
+        serde_json::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                lit(i)
+            } else if let Some(u) = n.as_u64() {
+                lit(u)
+            } else {
+                lit(n.as_f64().unwrap_or(f64::NAN))
+            }
+        }
+        serde_json::Value::String(s) => lit(s.clone()),
+        _ => return Err(format!("Unsupported filter value: {}", value)),
+    };
+
+    Ok(value.strict_cast(dtype.clone()))
+}
+
+/// Count rows and nulls of a LazyFrame without materialising the data.
+/// `collect` runs the counting query, which is meant for the streaming engine.
+pub fn count_rows_and_nulls<F>(lf: &LazyFrame, collect: F) -> Result<(usize, usize), String>
+where
+    F: FnOnce(LazyFrame) -> Result<DataFrame, String>,
+{
+    let counts = collect(
+        lf.clone()
+            .select([len().alias("__rows"), all().as_expr().null_count()]),
+    )?;
+
+    let mut values = counts.get_columns().iter().map(|column| {
+        column
+            .get(0)
+            .ok()
+            .and_then(|v| v.extract::<u64>())
+            .unwrap_or(0) as usize
+    });
+
+    let rows = values.next().unwrap_or(0);
+    let nulls = values.sum();
+
+    Ok((rows, nulls))
+}
+
 /// Convert DataFrame to rows (Vec<Vec<String>>)
 pub fn dataframe_to_rows(df: &DataFrame) -> Result<Vec<Vec<String>>, String> {
     let height = df.height();
@@ -44,32 +295,25 @@ pub fn dataframe_to_rows(df: &DataFrame) -> Result<Vec<Vec<String>>, String> {
                     }
                 }
             }
-            DataType::List(_) => {
-                // List types - format as [item1, item2, ...]
-                for row_idx in 0..height {
+            DataType::List(_) | DataType::Array(_, _) => {
+                // List and Array (fixed-size list) types - format as [item1, item2, ...]
+                for (row_idx, row) in rows.iter_mut().enumerate() {
                     let value = series.get(row_idx).unwrap();
-                    rows[row_idx][col_idx] = format_list_value(&value);
-                }
-            }
-            DataType::Array(_, _) => {
-                // Array types (fixed-size lists) - format as [item1, item2, ...]
-                for row_idx in 0..height {
-                    let value = series.get(row_idx).unwrap();
-                    rows[row_idx][col_idx] = format_list_value(&value);
+                    row[col_idx] = format_list_value(&value);
                 }
             }
             DataType::Struct(_) => {
                 // Struct types - format as {field1: value1, field2: value2, ...}
-                for row_idx in 0..height {
+                for (row_idx, row) in rows.iter_mut().enumerate() {
                     let value = series.get(row_idx).unwrap();
-                    rows[row_idx][col_idx] = format_struct_value(&value);
+                    row[col_idx] = format_struct_value(&value);
                 }
             }
             DataType::Duration(time_unit) => {
                 // Duration types - format as human-readable duration
-                for row_idx in 0..height {
+                for (row_idx, row) in rows.iter_mut().enumerate() {
                     let value = series.get(row_idx).unwrap();
-                    rows[row_idx][col_idx] = if value.is_null() {
+                    row[col_idx] = if value.is_null() {
                         String::new()
                     } else if let Ok(val) = value.try_extract::<i64>() {
                         format_duration(val, time_unit)
@@ -92,15 +336,15 @@ pub fn dataframe_to_rows(df: &DataFrame) -> Result<Vec<Vec<String>>, String> {
                             }
                         } else {
                             // Fallback to debug format if str() fails
-                            for row_idx in 0..height {
-                                rows[row_idx][col_idx] = format!("{:?}", series.get(row_idx).unwrap());
+                            for (row_idx, row) in rows.iter_mut().enumerate() {
+                                row[col_idx] = format!("{:?}", series.get(row_idx).unwrap());
                             }
                         }
                     }
                     Err(_) => {
                         // If cast fails, use debug format as fallback
-                        for row_idx in 0..height {
-                            rows[row_idx][col_idx] = format!("{:?}", series.get(row_idx).unwrap());
+                        for (row_idx, row) in rows.iter_mut().enumerate() {
+                            row[col_idx] = format!("{:?}", series.get(row_idx).unwrap());
                         }
                     }
                 }
@@ -111,50 +355,148 @@ pub fn dataframe_to_rows(df: &DataFrame) -> Result<Vec<Vec<String>>, String> {
     Ok(rows)
 }
 
-/// Calculate statistics for all columns in a DataFrame
-pub fn calculate_statistics(
-    df: &DataFrame,
-) -> Result<HashMap<String, HashMap<String, serde_json::Value>>, String> {
+/// Calculate statistics for some columns of a LazyFrame, or all columns if
+/// `columns` is `None`, without collecting it. Aggregates of every column run
+/// as one query; only top values and date/time statistics, which need the
+/// values themselves, read a single column each. Statistics in `known`, e.g.
+/// from a Parquet footer, are not computed again. `collect` runs each query,
+/// which is meant for the streaming engine.
+pub fn calculate_statistics_lazy<F>(
+    lf: &LazyFrame,
+    columns: Option<&[String]>,
+    mut known: HashMap<String, HashMap<String, serde_json::Value>>,
+    collect: F,
+) -> Result<HashMap<String, HashMap<String, serde_json::Value>>, String>
+where
+    F: Fn(LazyFrame) -> Result<DataFrame, String>,
+{
+    let schema = lf
+        .clone()
+        .collect_schema()
+        .map_err(|e| format!("Failed to read schema: {}", e))?;
+
+    let mut selected = Vec::new();
+    match columns {
+        Some(columns) => {
+            for name in columns {
+                selected.push((name.clone(), column_dtype(&schema, name)?.clone()));
+            }
+        }
+        None => {
+            for (name, dtype) in schema.iter() {
+                selected.push((name.to_string(), dtype.clone()));
+            }
+        }
+    }
+
+    // One row holding the aggregates of every column
+    let mut aggregations = Vec::new();
+    for (idx, (name, dtype)) in selected.iter().enumerate() {
+        let column_known = known.get(name.as_str());
+        aggregations.extend(column_aggregations(idx, name, dtype, column_known));
+    }
+    let aggregates = if aggregations.is_empty() {
+        None
+    } else {
+        Some(collect(lf.clone().select(aggregations))?)
+    };
+    let aggregate = |idx: usize, key: &str| -> Option<f64> {
+        aggregates
+            .as_ref()?
+            .column(&aggregate_name(idx, key))
+            .ok()?
+            .get(0)
+            .ok()?
+            .extract::<f64>()
+    };
+
     let mut stats: HashMap<String, HashMap<String, serde_json::Value>> = HashMap::new();
 
-    for series in df.get_columns() {
-        let column_name = series.name().to_string();
-        let mut column_stats: HashMap<String, serde_json::Value> = HashMap::new();
+    for (idx, (name, dtype)) in selected.into_iter().enumerate() {
+        let mut column_stats = known.remove(&name).unwrap_or_default();
 
         // Null count (applicable to all types)
-        let null_count = series.null_count();
-        column_stats.insert("null_values".to_string(), serde_json::json!(null_count));
+        if let Some(null_count) = aggregate(idx, "null_values") {
+            column_stats.insert(
+                "null_values".to_string(),
+                serde_json::json!(null_count as u64),
+            );
+        }
 
-        match series.dtype() {
+        match dtype {
             DataType::Boolean => {
                 // Boolean statistics - count true/false values
-                if let Ok(ca) = series.bool() {
-                    let total = series.len() - null_count;
-                    let true_count = ca.sum().unwrap_or(0) as usize;
-                    let false_count = total - true_count;
+                let total = aggregate(idx, "count").unwrap_or(0.0) as usize;
+                let true_count = aggregate(idx, "true_count").unwrap_or(0.0) as usize;
+                let false_count = total - true_count;
 
-                    let true_pct = if total > 0 {
-                        (true_count as f64 / total as f64) * 100.0
-                    } else {
-                        0.0
-                    };
-                    let false_pct = if total > 0 {
-                        (false_count as f64 / total as f64) * 100.0
-                    } else {
-                        0.0
-                    };
+                let true_pct = if total > 0 {
+                    (true_count as f64 / total as f64) * 100.0
+                } else {
+                    0.0
+                };
+                let false_pct = if total > 0 {
+                    (false_count as f64 / total as f64) * 100.0
+                } else {
+                    0.0
+                };
 
+                column_stats.insert(
+                    "true_count".to_string(),
+                    serde_json::json!(format!("{} ({:.1}%)", true_count, true_pct)),
+                );
+                column_stats.insert(
+                    "false_count".to_string(),
+                    serde_json::json!(format!("{} ({:.1}%)", false_count, false_pct)),
+                );
+            }
+            ref dtype if is_numeric(dtype) => {
+                // Numeric statistics
+                for key in NUMERIC_STATISTICS {
+                    if let Some(value) = aggregate(idx, key) {
+                        column_stats.insert(key.to_string(), serde_json::json!(value));
+                    }
+                }
+            }
+            DataType::Date | DataType::Datetime(_, _) | DataType::Time | DataType::Duration(_) => {
+                // Date/time statistics, aggregated in their own queries
+                temporal_statistics(lf, &name, &dtype, &collect, &mut column_stats)?;
+            }
+            DataType::String => {
+                // String statistics - unique count, lengths, top values and patterns
+                string_statistics(lf, &name, idx, aggregate, &collect, &mut column_stats)?;
+            }
+            _ => {
+                // Other types - unique count
+                if let Some(unique_count) = aggregate(idx, "unique_values") {
                     column_stats.insert(
-                        "true_count".to_string(),
-                        serde_json::json!(format!("{} ({:.1}%)", true_count, true_pct))
-                    );
-                    column_stats.insert(
-                        "false_count".to_string(),
-                        serde_json::json!(format!("{} ({:.1}%)", false_count, false_pct))
+                        "unique_values".to_string(),
+                        serde_json::json!(unique_count as u64),
                     );
                 }
             }
-            DataType::Int8
+        }
+
+        stats.insert(name, column_stats);
+    }
+
+    Ok(stats)
+}
+
+/// Statistics of numeric columns, computed on values cast to Float64
+const NUMERIC_STATISTICS: [&str; 6] = [
+    "min",
+    "max",
+    "mean",
+    "median",
+    "percentile_25",
+    "percentile_75",
+];
+
+pub(super) fn is_numeric(dtype: &DataType) -> bool {
+    matches!(
+        dtype,
+        DataType::Int8
             | DataType::Int16
             | DataType::Int32
             | DataType::Int64
@@ -163,69 +505,425 @@ pub fn calculate_statistics(
             | DataType::UInt32
             | DataType::UInt64
             | DataType::Float32
-            | DataType::Float64 => {
-                // Numeric statistics
-                if let Ok(ca) = series.cast(&DataType::Float64) {
-                    if let Ok(series_f64) = ca.f64() {
-                        if let Some(min) = series_f64.min() {
-                            column_stats.insert("min".to_string(), serde_json::json!(min));
-                        }
-                        if let Some(max) = series_f64.max() {
-                            column_stats.insert("max".to_string(), serde_json::json!(max));
-                        }
-                        if let Some(mean) = series_f64.mean() {
-                            column_stats.insert("mean".to_string(), serde_json::json!(mean));
-                        }
-                        if let Some(median) = series_f64.median() {
-                            column_stats.insert("median".to_string(), serde_json::json!(median));
-                        }
-                        if let Some(q25) = series_f64
-                            .quantile(0.25, QuantileMethod::Linear)
-                            .ok()
-                            .flatten()
-                        {
-                            column_stats
-                                .insert("percentile_25".to_string(), serde_json::json!(q25));
-                        }
-                        if let Some(q75) = series_f64
-                            .quantile(0.75, QuantileMethod::Linear)
-                            .ok()
-                            .flatten()
-                        {
-                            column_stats
-                                .insert("percentile_75".to_string(), serde_json::json!(q75));
-                        }
-                    }
+            | DataType::Float64
+    )
+}
+
+/// Name of an aggregate in the statistics query. Column names may contain
+/// anything, so aggregates are named by column position.
+fn aggregate_name(idx: usize, key: &str) -> String {
+    format!("__{}_{}", idx, key)
+}
+
+/// Aggregate expressions for the statistics of one column, skipping
+/// statistics that are already known
+fn column_aggregations(
+    idx: usize,
+    name: &str,
+    dtype: &DataType,
+    known: Option<&HashMap<String, serde_json::Value>>,
+) -> Vec<Expr> {
+    let is_known = |key: &str| known.is_some_and(|stats| stats.contains_key(key));
+    let column = col(name);
+    let mut exprs = Vec::new();
+
+    if !is_known("null_values") {
+        exprs.push(
+            column
+                .clone()
+                .null_count()
+                .alias(aggregate_name(idx, "null_values")),
+        );
+    }
+
+    match dtype {
+        DataType::Boolean => {
+            exprs.push(column.clone().count().alias(aggregate_name(idx, "count")));
+            exprs.push(column.sum().alias(aggregate_name(idx, "true_count")));
+        }
+        dtype if is_numeric(dtype) => {
+            let values = column.cast(DataType::Float64);
+            for key in NUMERIC_STATISTICS {
+                if is_known(key) {
+                    continue;
                 }
+                let expr = match key {
+                    "min" => values.clone().min(),
+                    "max" => values.clone().max(),
+                    "mean" => values.clone().mean(),
+                    "median" => values.clone().median(),
+                    "percentile_25" => values.clone().quantile(lit(0.25), QuantileMethod::Linear),
+                    _ => values.clone().quantile(lit(0.75), QuantileMethod::Linear),
+                };
+                exprs.push(expr.alias(aggregate_name(idx, key)));
             }
-            DataType::Date | DataType::Datetime(_, _) | DataType::Time | DataType::Duration(_) => {
-                // Date/time statistics (min, max) - simplified for now
-                // These can be improved with proper datetime handling
-            }
-            _ => {
-                // String and other types - unique count
-                if let Ok(unique_count) = series.unique().map(|u| u.len()) {
-                    column_stats
-                        .insert("unique_values".to_string(), serde_json::json!(unique_count));
-                }
+        }
+        DataType::Date | DataType::Datetime(_, _) | DataType::Time | DataType::Duration(_) => {}
+        DataType::String => {
+            // Lengths in characters
+            let lengths = column.clone().str().len_chars();
+            exprs.extend([
+                column.clone().count().alias(aggregate_name(idx, "count")),
+                column
+                    .clone()
+                    .n_unique()
+                    .alias(aggregate_name(idx, "unique_values")),
+                lengths
+                    .clone()
+                    .min()
+                    .alias(aggregate_name(idx, "min_length")),
+                lengths
+                    .clone()
+                    .max()
+                    .alias(aggregate_name(idx, "max_length")),
+                lengths
+                    .clone()
+                    .mean()
+                    .alias(aggregate_name(idx, "mean_length")),
+                lengths
+                    .eq(lit(0))
+                    .sum()
+                    .alias(aggregate_name(idx, "empty_values")),
+            ]);
+            for (pattern_name, pattern) in STRING_PATTERNS {
+                exprs.push(
+                    column
+                        .clone()
+                        .str()
+                        .contains(lit(pattern), true)
+                        .sum()
+                        .alias(aggregate_name(idx, pattern_name)),
+                );
             }
         }
+        _ => exprs.push(
+            column
+                .n_unique()
+                .alias(aggregate_name(idx, "unique_values")),
+        ),
+    }
+
+    exprs
+}
+
+/// Number of most frequent values listed for string columns
+const TOP_VALUES: usize = 10;
+
+/// Share of non-empty values that must match a pattern for it to be reported
+const PATTERN_THRESHOLD: f64 = 0.9;
+
+/// Patterns recognised in string columns
+const STRING_PATTERNS: [(&str, &str); 7] = [
+    ("email", r"^[^@\s]+@[^@\s]+\.[^@\s]+$"),
+    ("url", r"^https?://\S+$"),
+    (
+        "uuid",
+        r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$",
+    ),
+    ("iso_date", r"^\d{4}-\d{2}-\d{2}$"),
+    (
+        "iso_datetime",
+        r"^\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}(:\d{2}(\.\d+)?)?(Z|[+-]\d{2}:?\d{2})?$",
+    ),
+    ("integer", r"^[+-]?\d+$"),
+    ("decimal", r"^[+-]?(\d+\.\d*|\.\d+)([eE][+-]?\d+)?$"),
+];
+
+/// Statistics for String columns: value lengths, empty strings, the most
+/// frequent values and what the values look like
+fn string_statistics<A, F>(
+    lf: &LazyFrame,
+    name: &str,
+    idx: usize,
+    aggregate: A,
+    collect: F,
+    column_stats: &mut HashMap<String, serde_json::Value>,
+) -> Result<(), String>
+where
+    A: Fn(usize, &str) -> Option<f64>,
+    F: Fn(LazyFrame) -> Result<DataFrame, String>,
+{
+    for key in ["unique_values", "min_length", "max_length", "empty_values"] {
+        if let Some(value) = aggregate(idx, key) {
+            column_stats.insert(key.to_string(), serde_json::json!(value as u64));
+        }
+    }
+    if let Some(mean) = aggregate(idx, "mean_length") {
+        column_stats.insert("mean_length".to_string(), serde_json::json!(mean));
+    }
 
-        stats.insert(column_name, column_stats);
+    // Most frequent values, ties broken by value
+    let top = collect(
+        lf.clone()
+            .select([col(name)])
+            .drop_nulls(None)
+            .group_by([col(name)])
+            .agg([len().alias("__count")])
+            .sort_by_exprs(
+                [col("__count"), col(name)],
+                SortMultipleOptions::default().with_order_descending_multi([true, false]),
+            )
+            .limit(TOP_VALUES as IdxSize),
+    )?;
+    let values = top.column(name).ok().and_then(|c| c.str().ok().cloned());
+    let counts = top
+        .column("__count")
+        .ok()
+        .and_then(|c| c.cast(&DataType::UInt64).ok())
+        .and_then(|c| c.u64().ok().cloned());
+    if let (Some(values), Some(counts)) = (values, counts) {
+        let top_values: Vec<serde_json::Value> = values
+            .into_iter()
+            .zip(&counts)
+            .map(
+                |(value, count)| serde_json::json!({ "value": value, "count": count.unwrap_or(0) }),
+            )
+            .collect();
+        column_stats.insert("top_values".to_string(), serde_json::json!(top_values));
     }
 
-    Ok(stats)
+    // Patterns shared by most non-empty values, e.g. numbers stored as text
+    let non_empty =
+        aggregate(idx, "count").unwrap_or(0.0) - aggregate(idx, "empty_values").unwrap_or(0.0);
+    if non_empty <= 0.0 {
+        return Ok(());
+    }
+    let patterns: Vec<String> = STRING_PATTERNS
+        .iter()
+        .filter_map(|(pattern_name, _)| {
+            let share = aggregate(idx, pattern_name)? / non_empty;
+            (share >= PATTERN_THRESHOLD)
+                .then(|| format!("{} ({:.1}%)", pattern_name, share * 100.0))
+        })
+        .collect();
+    if !patterns.is_empty() {
+        column_stats.insert(
+            "patterns".to_string(),
+            serde_json::json!(patterns.join(", ")),
+        );
+    }
+
+    Ok(())
+}
+
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Statistics for Date, Datetime, Time and Duration columns, computed with
+/// aggregations so the column is not collected. Values are formatted as in
+/// the table, in the time zone of the column.
+fn temporal_statistics<F>(
+    lf: &LazyFrame,
+    name: &str,
+    dtype: &DataType,
+    collect: F,
+    column_stats: &mut HashMap<String, serde_json::Value>,
+) -> Result<(), String>
+where
+    F: Fn(LazyFrame) -> Result<DataFrame, String>,
+{
+    let column = col(name);
+    // Physical values as i128, so ranges and gaps of values far from 1970
+    // do not overflow
+    let physical = |expr: Expr| expr.to_physical().cast(DataType::Int128);
+
+    let bounds = collect(lf.clone().select([
+        column.clone().count().alias("count"),
+        column.clone().min().alias("min"),
+        column.clone().max().alias("max"),
+        physical(column.clone()).min().alias("min_physical"),
+        physical(column.clone()).max().alias("max_physical"),
+    ]))?;
+    let count = first_value(&bounds, "count")
+        .and_then(|value| value.extract::<usize>())
+        .unwrap_or(0);
+    if count == 0 {
+        return Ok(());
+    }
+
+    let is_dated = matches!(dtype, DataType::Date | DataType::Datetime(_, _));
+    let has_hours = matches!(dtype, DataType::Datetime(_, _) | DataType::Time);
+
+    // The median is the lower middle value; gaps are between consecutive values
+    let sorted = column.clone().drop_nulls().sort(SortOptions::default());
+    let mut exprs = vec![sorted
+        .clone()
+        .get(lit(((count - 1) / 2) as IdxSize))
+        .alias("median")];
+    if is_dated {
+        // 1 = Monday
+        let weekday = column.clone().dt().weekday();
+        exprs.extend((1..=7).map(|day| {
+            weekday
+                .clone()
+                .eq(lit(day))
+                .sum()
+                .alias(format!("weekday_{}", day))
+        }));
+        if count > 1 {
+            // Gap from each value to the next; the last of the largest is reported
+            let values = physical(sorted.clone());
+            let gaps = values.clone().shift(lit(-1)) - values;
+            exprs.push(gaps.clone().max().alias("largest_gap"));
+            exprs.push(
+                sorted
+                    .filter(gaps.clone().eq(gaps.max()))
+                    .last()
+                    .alias("largest_gap_start"),
+            );
+        }
+    }
+    if has_hours {
+        let hour = column.dt().hour();
+        exprs.extend((0..24).map(|h| hour.clone().eq(lit(h)).sum().alias(format!("hour_{}", h))));
+    }
+    let values = collect(lf.clone().select(exprs))?;
+
+    for (key, frame) in [("min", &bounds), ("max", &bounds), ("median", &values)] {
+        if let Some(value) = format_temporal_column(frame, key) {
+            column_stats.insert(key.to_string(), serde_json::json!(value));
+        }
+    }
+    let physical_value = |frame: &DataFrame, key: &str| first_value(frame, key)?.extract::<i128>();
+    if let (Some(min), Some(max)) = (
+        physical_value(&bounds, "min_physical"),
+        physical_value(&bounds, "max_physical"),
+    ) {
+        if let Some(range) = physical_micros(dtype, max - min) {
+            column_stats.insert("range".to_string(), serde_json::json!(format_micros(range)));
+        }
+    }
+
+    let counts = |prefix: &str, values_range: std::ops::RangeInclusive<usize>| {
+        values_range
+            .map(|value| {
+                let count = first_value(&values, &format!("{}_{}", prefix, value))
+                    .and_then(|count| count.extract::<usize>())
+                    .unwrap_or(0);
+                (value, count)
+            })
+            .collect::<Vec<_>>()
+    };
+    let total = count as f64;
+
+    // Most common day of the week
+    if is_dated {
+        if let Some((day, day_count)) = most_common(counts("weekday", 1..=7)) {
+            column_stats.insert(
+                "most_common_weekday".to_string(),
+                serde_json::json!(format!(
+                    "{} ({:.1}%)",
+                    WEEKDAYS[day.saturating_sub(1).min(6)],
+                    day_count as f64 / total * 100.0
+                )),
+            );
+        }
+    }
+
+    // Most common hour of the day
+    if has_hours {
+        if let Some((hour, hour_count)) = most_common(counts("hour", 0..=23)) {
+            column_stats.insert(
+                "most_common_hour".to_string(),
+                serde_json::json!(format!(
+                    "{:02}:00 ({:.1}%)",
+                    hour,
+                    hour_count as f64 / total * 100.0
+                )),
+            );
+        }
+    }
+
+    // Largest interval between consecutive timestamps
+    if let Some(gap) =
+        physical_value(&values, "largest_gap").and_then(|gap| physical_micros(dtype, gap))
+    {
+        column_stats.insert(
+            "largest_gap".to_string(),
+            serde_json::json!(format_micros(gap)),
+        );
+        if let Some(start) = format_temporal_column(&values, "largest_gap_start") {
+            column_stats.insert("largest_gap_start".to_string(), serde_json::json!(start));
+        }
+    }
+
+    Ok(())
+}
+
+/// First value of a column of a one-row result
+fn first_value<'a>(df: &'a DataFrame, name: &str) -> Option<AnyValue<'a>> {
+    df.column(name).ok()?.get(0).ok()
+}
+
+/// A physical temporal value, or a difference of two, in microseconds
+fn physical_micros(dtype: &DataType, value: i128) -> Option<i128> {
+    match dtype {
+        DataType::Date => Some(value * 86_400_000_000),
+        DataType::Time => Some(value / 1_000),
+        DataType::Datetime(time_unit, _) | DataType::Duration(time_unit) => match time_unit {
+            TimeUnit::Nanoseconds => Some(value / 1_000),
+            TimeUnit::Microseconds => Some(value),
+            TimeUnit::Milliseconds => Some(value * 1_000),
+        },
+        _ => None,
+    }
+}
+
+/// Format the value of a one-row temporal column
+fn format_temporal_column(df: &DataFrame, name: &str) -> Option<String> {
+    let series = df.column(name).ok()?.as_materialized_series();
+    if series.null_count() > 0 {
+        return None;
+    }
+    format_temporal_at(series, 0)
+}
+
+/// Format one value of a temporal Series
+fn format_temporal_at(series: &Series, idx: usize) -> Option<String> {
+    let value = series.slice(idx as i64, 1);
+
+    if let DataType::Duration(time_unit) = series.dtype() {
+        let physical = value.to_physical_repr();
+        let v = physical.i64().ok()?.get(0)?;
+        return Some(format_duration(v, time_unit));
+    }
+
+    let text = value.cast(&DataType::String).ok()?;
+    let text = text.str().ok()?.get(0)?.to_string();
+    Some(text)
+}
+
+/// Most frequent value and its count, from the counts of each value. Ties go
+/// to the smallest value.
+fn most_common(counts: Vec<(usize, usize)>) -> Option<(usize, usize)> {
+    counts
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .max_by_key(|(value, count)| (*count, std::cmp::Reverse(*value)))
 }
 
 /// Format duration value as human-readable string (e.g., "21 days 12:00:00")
 fn format_duration(value: i64, time_unit: &polars::prelude::TimeUnit) -> String {
     // Convert to microseconds for consistent handling
     let microseconds = match time_unit {
-        polars::prelude::TimeUnit::Nanoseconds => value / 1_000,
-        polars::prelude::TimeUnit::Microseconds => value,
-        polars::prelude::TimeUnit::Milliseconds => value * 1_000,
+        polars::prelude::TimeUnit::Nanoseconds => value as i128 / 1_000,
+        polars::prelude::TimeUnit::Microseconds => value as i128,
+        polars::prelude::TimeUnit::Milliseconds => value as i128 * 1_000,
     };
 
+    format_micros(microseconds)
+}
+
+/// Format a duration in microseconds. Differences between dates can exceed
+/// the range of i64 microseconds, so they are computed in i128.
+fn format_micros(microseconds: i128) -> String {
     let is_negative = microseconds < 0;
     let abs_microseconds = microseconds.abs();
 
@@ -239,7 +937,10 @@ fn format_duration(value: i64, time_unit: &polars::prelude::TimeUnit) -> String
     let sign = if is_negative { "-" } else { "" };
 
     if days > 0 {
-        format!("{}{} days {:02}:{:02}:{:02}", sign, days, hours, minutes, seconds)
+        format!(
+            "{}{} days {:02}:{:02}:{:02}",
+            sign, days, hours, minutes, seconds
+        )
     } else {
         format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, seconds)
     }
@@ -250,7 +951,7 @@ fn format_list_value(value: &polars::prelude::AnyValue) -> String {
     use polars::prelude::AnyValue;
 
     match value {
-        AnyValue::List(series) => {
+        AnyValue::List(series) | AnyValue::Array(series, _) => {
             let items: Vec<String> = (0..series.len())
                 .map(|i| {
                     let item = series.get(i).unwrap();
@@ -259,49 +960,8 @@ fn format_list_value(value: &polars::prelude::AnyValue) -> String {
                 .collect();
             format!("[{}]", items.join(", "))
         }
-        // Handle Array type (fixed-size list) - extract from debug format or convert
-        _ => {
-            // Check if it's an array by converting to string and parsing
-            let debug_str = format!("{:?}", value);
-            if debug_str.starts_with("Array(") {
-                // Extract just the values from the Array debug format
-                // Try to extract the series and format it
-                if let AnyValue::Null = value {
-                    String::new()
-                } else {
-                    // For arrays, we'll use a simpler approach - just show the type
-                    // since extracting values from Array AnyValue is complex
-                    debug_str
-                        .split("Series: '' [")
-                        .nth(1)
-                        .and_then(|s| s.split(']').next())
-                        .map(|type_str| {
-                            // Try to extract the values
-                            if let Some(values_part) = debug_str.split("[\n").nth(1) {
-                                let values: Vec<String> = values_part
-                                    .lines()
-                                    .filter_map(|line| {
-                                        let trimmed = line.trim();
-                                        if !trimmed.is_empty() && trimmed != "]" && !trimmed.starts_with("],") {
-                                            Some(trimmed.to_string())
-                                        } else {
-                                            None
-                                        }
-                                    })
-                                    .collect();
-                                format!("[{}]", values.join(", "))
-                            } else {
-                                format!("array<{}>", type_str)
-                            }
-                        })
-                        .unwrap_or_else(|| debug_str)
-                }
-            } else if let AnyValue::Null = value {
-                String::new()
-            } else {
-                format!("{:?}", value)
-            }
-        }
+        AnyValue::Null => String::new(),
+        _ => format!("{:?}", value),
     }
 }
 
@@ -317,9 +977,16 @@ fn format_struct_value(value: &polars::prelude::AnyValue) -> String {
                 if field_idx < values.len() {
                     // Convert Arrow array to Polars Series for easier value extraction
                     let arrow_array = &values[field_idx];
-                    if let Ok(series) = polars::prelude::Series::try_from((field.name().clone(), arrow_array.clone())) {
+                    if let Ok(series) = polars::prelude::Series::try_from((
+                        field.name().clone(),
+                        arrow_array.clone(),
+                    )) {
                         if let Ok(field_value) = series.get(*idx) {
-                            field_strs.push(format!("{}: {}", field.name(), format_any_value_simple(&field_value)));
+                            field_strs.push(format!(
+                                "{}: {}",
+                                field.name(),
+                                format_any_value_simple(&field_value)
+                            ));
                         }
                     }
                 }
@@ -331,7 +998,7 @@ fn format_struct_value(value: &polars::prelude::AnyValue) -> String {
             format!("{:?}", value)
         }
         AnyValue::Null => String::new(),
-        _ => format!("{:?}", value)
+        _ => format!("{:?}", value),
     }
 }
 
@@ -354,7 +1021,7 @@ fn format_any_value_simple(value: &polars::prelude::AnyValue) -> String {
         AnyValue::UInt64(v) => v.to_string(),
         AnyValue::Float32(v) => format!("{:.6}", v),
         AnyValue::Float64(v) => format!("{:.6}", v),
-        _ => format!("{:?}", value)
+        _ => format!("{:?}", value),
     }
 }
 
@@ -378,7 +1045,7 @@ fn format_any_value(value: &polars::prelude::AnyValue) -> String {
         AnyValue::UInt64(v) => v.to_string(),
         AnyValue::Float32(v) => v.to_string(),
         AnyValue::Float64(v) => v.to_string(),
-        AnyValue::List(series) => {
+        AnyValue::List(series) | AnyValue::Array(series, _) => {
             let items: Vec<String> = (0..series.len().min(3))
                 .map(|i| format_any_value(&series.get(i).unwrap()))
                 .collect();
@@ -392,6 +1059,139 @@ fn format_any_value(value: &polars::prelude::AnyValue) -> String {
             // For nested structs in lists, use simplified format
             "struct".to_string()
         }
-        _ => format!("{:?}", value)
+        _ => format!("{:?}", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn filtered(filters: serde_json::Value) -> Result<Vec<i64>, String> {
+        let df = df!("x" => [0i64, 1, 2, 3]).unwrap();
+        let filters: Vec<Filter> = serde_json::from_value(filters).unwrap();
+        let df = apply_filters(df.lazy(), filters)?.collect().unwrap();
+        Ok(df
+            .column("x")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect())
+    }
+
+    #[test]
+    fn fractional_values_compare_integers_as_floats() {
+        let compare = |op: &str, value: f64| {
+            filtered(json!([{"kind": "compare", "column": "x", "op": op, "value": value}])).unwrap()
+        };
+        assert_eq!(compare("lt", 1.5), vec![0, 1]);
+        assert_eq!(compare("gt", 1.5), vec![2, 3]);
+        assert_eq!(compare("gt_eq", -0.5), vec![0, 1, 2, 3]);
+        // Whole numbers written as floats are cast to the column type
+        assert_eq!(compare("eq", 2.0), vec![2]);
+
+        let between =
+            filtered(json!([{"kind": "between", "column": "x", "low": 0.5, "high": 2.5}]));
+        assert_eq!(between.unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn fractional_equality_on_integers_is_rejected() {
+        let eq = filtered(json!([{"kind": "compare", "column": "x", "op": "eq", "value": 1.5}]));
+        assert!(eq.unwrap_err().contains("holds integers"));
+        let values = filtered(json!([{"kind": "in", "column": "x", "values": [1, 2.5]}]));
+        assert!(values.is_err());
+    }
+
+    #[test]
+    fn temporal_ranges_far_from_1970_do_not_overflow() {
+        let stats = |series: Series| {
+            let dtype = series.dtype().clone();
+            let lf = DataFrame::new(vec![series.into()]).unwrap().lazy();
+            let mut column_stats = HashMap::new();
+            let collect = |lf: LazyFrame| lf.collect().map_err(|e| e.to_string());
+            temporal_statistics(&lf, "t", &dtype, collect, &mut column_stats).unwrap();
+            column_stats
+        };
+
+        let dates = Series::new("t".into(), [-90_000_000i32, 90_000_000])
+            .cast(&DataType::Date)
+            .unwrap();
+        assert_eq!(stats(dates)["range"], json!("180000000 days 00:00:00"));
+
+        let millis = [-8_000_000_000_000_000i64, 8_000_000_000_000_000];
+        let millis = Series::new("t".into(), millis)
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+            .unwrap();
+        let column_stats = stats(millis);
+        assert_eq!(column_stats["range"], json!("185185185 days 04:26:40"));
+        assert_eq!(column_stats["largest_gap"], column_stats["range"]);
+    }
+
+    #[test]
+    fn string_statistics_list_top_values_lengths_and_patterns() {
+        let mut values = vec![Some("10"), Some("7"), Some("x-1"), Some(""), None, None];
+        values.extend([Some("10"), Some("10"), Some("7"), Some("7")]);
+        values.extend(["9", "8", "6", "5", "4", "3", "2", "1"].map(Some));
+        let lf = df!("s" => values).unwrap().lazy();
+        let collect = |lf: LazyFrame| lf.collect().map_err(|e| e.to_string());
+        let stats = calculate_statistics_lazy(&lf, None, HashMap::new(), collect).unwrap();
+        let stats = &stats["s"];
+
+        // Ties are ordered by value, the empty string included; 9 and x-1 are left out
+        let top: Vec<_> = stats["top_values"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|top| {
+                (
+                    top["value"].as_str().unwrap(),
+                    top["count"].as_u64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            top,
+            [
+                ("10", 3),
+                ("7", 3),
+                ("", 1),
+                ("1", 1),
+                ("2", 1),
+                ("3", 1),
+                ("4", 1),
+                ("5", 1),
+                ("6", 1),
+                ("8", 1)
+            ]
+        );
+
+        assert_eq!(stats["null_values"], json!(2));
+        assert_eq!(stats["empty_values"], json!(1));
+        assert_eq!(stats["min_length"], json!(0));
+        assert_eq!(stats["max_length"], json!(3));
+        assert_eq!(stats["mean_length"], json!(1.25));
+        // 12 distinct strings and null
+        assert_eq!(stats["unique_values"], json!(13));
+
+        // 14 of the 15 non-empty values are integers; none has a decimal point
+        assert_eq!(stats["patterns"], json!("integer (93.3%)"));
+    }
+
+    #[test]
+    fn filters_combine_and_report_unknown_columns() {
+        let filters = json!([
+            {"kind": "or", "filters": [
+                {"kind": "compare", "column": "x", "op": "eq", "value": 0},
+                {"kind": "in", "column": "x", "values": [2, 3]},
+            ]},
+            {"kind": "compare", "column": "x", "op": "not_eq", "value": 3},
+        ]);
+        assert_eq!(filtered(filters).unwrap(), vec![0, 2]);
+
+        let missing = filtered(json!([{"kind": "is_null", "column": "y"}]));
+        assert_eq!(missing.unwrap_err(), "Column not found: y");
     }
 }
@@ -0,0 +1,204 @@
+use polars::prelude::*;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use super::csv_loader::{open_csv, sniff_csv, CsvOptions};
+use super::ipc_loader::{open_ipc, sniff_ipc};
+use super::json_loader::{open_json, sniff_json};
+use super::parquet_loader::{is_glob, is_parquet_dataset, open_parquet, sniff_parquet};
+
+/// Number of leading bytes read when sniffing a file's format
+const SNIFF_LEN: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatKind {
+    Parquet,
+    Csv,
+    Json,
+    Ipc,
+}
+
+/// Format-specific options passed to the openers. Formats ignore the
+/// options that do not apply to them.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ReadOptions {
+    pub csv: Option<CsvOptions>,
+}
+
+/// A file format that can be opened as a LazyFrame
+pub struct FileFormat {
+    pub kind: FormatKind,
+    pub name: &'static str,
+    /// Lowercase file extensions, without the leading dot
+    pub extensions: &'static [&'static str],
+    /// Returns true if the leading bytes of a file look like this format
+    pub sniff: fn(&[u8]) -> bool,
+    pub open: fn(&str, &ReadOptions) -> Result<LazyFrame, String>,
+}
+
+/// All known formats. Formats with reliable magic bytes come first so that
+/// weaker text-based sniffers never shadow them.
+static FORMATS: &[FileFormat] = &[
+    FileFormat {
+        kind: FormatKind::Parquet,
+        name: "Parquet",
+        extensions: &["parquet", "pq"],
+        sniff: sniff_parquet,
+        open: |path, _| open_parquet(path),
+    },
+    FileFormat {
+        kind: FormatKind::Ipc,
+        name: "Arrow IPC",
+        extensions: &["arrow", "feather", "ipc", "arrows"],
+        sniff: sniff_ipc,
+        open: |path, _| open_ipc(path),
+    },
+    FileFormat {
+        kind: FormatKind::Json,
+        name: "JSON",
+        extensions: &["json", "jsonl", "ndjson"],
+        sniff: sniff_json,
+        open: |path, _| open_json(path),
+    },
+    FileFormat {
+        kind: FormatKind::Csv,
+        name: "CSV",
+        extensions: &["csv", "tsv", "txt"],
+        sniff: sniff_csv,
+        open: |path, options| open_csv(path, options.csv.as_ref()),
+    },
+];
+
+/// Resolve the format of a file from its content and extension.
+///
+/// The extension wins when its format's sniffer agrees with the content.
+/// Otherwise the first sniffer that recognises the content is used, so
+/// misnamed files still open. If no sniffer matches (e.g. an empty file)
+/// the extension alone decides. Directories and globs are Parquet datasets.
+pub fn resolve_format(file_path: &str) -> Result<&'static FileFormat, String> {
+    if is_parquet_dataset(file_path) {
+        return Ok(format_of_kind(FormatKind::Parquet));
+    }
+
+    let header = read_header(file_path)?;
+    let by_extension = format_for_extension(file_path);
+
+    if let Some(format) = by_extension {
+        if (format.sniff)(&header) {
+            return Ok(format);
+        }
+    }
+
+    if let Some(format) = FORMATS.iter().find(|f| (f.sniff)(&header)) {
+        return Ok(format);
+    }
+
+    by_extension.ok_or_else(|| format!("Unsupported file format: {}", file_path))
+}
+
+/// Open any supported file as a LazyFrame
+pub fn open_file(file_path: &str, options: &ReadOptions) -> Result<LazyFrame, String> {
+    let format = resolve_format(file_path)?;
+    (format.open)(file_path, options)
+}
+
+/// Path to hand to a Polars scan. Polars expands glob characters in any path,
+/// so they are escaped unless the path is a glob pattern.
+pub(super) fn scan_path(file_path: &str) -> PlPath {
+    if is_glob(file_path) {
+        PlPath::from_str(file_path)
+    } else {
+        PlPath::from_string(glob::Pattern::escape(file_path))
+    }
+}
+
+fn format_of_kind(kind: FormatKind) -> &'static FileFormat {
+    FORMATS
+        .iter()
+        .find(|f| f.kind == kind)
+        .expect("every format kind is registered")
+}
+
+fn format_for_extension(file_path: &str) -> Option<&'static FileFormat> {
+    let extension = Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())?
+        .to_lowercase();
+
+    FORMATS
+        .iter()
+        .find(|f| f.extensions.contains(&extension.as_str()))
+}
+
+fn read_header(file_path: &str) -> Result<Vec<u8>, String> {
+    let file = File::open(file_path).map_err(|e| format!("Failed to open file: {}", e))?;
+
+    let mut header = Vec::with_capacity(SNIFF_LEN);
+    file.take(SNIFF_LEN as u64)
+        .read_to_end(&mut header)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_loader::test_utils::{path_str, temp_dir, write_parquet};
+
+    #[test]
+    fn formats_are_sniffed_from_the_content() {
+        let dir = temp_dir("sniff-formats");
+        let mut df = df!("a" => [1i64, 2]).unwrap();
+
+        // Misnamed files open as the format of their content
+        let parquet = dir.join("data.csv");
+        write_parquet(&parquet, &mut df, 10);
+        let json = dir.join("events.txt");
+        std::fs::write(&json, "{\"a\": 1}\n{\"a\": 2}\n").unwrap();
+        let ipc = dir.join("frame.bin");
+        IpcWriter::new(File::create(&ipc).unwrap())
+            .finish(&mut df)
+            .unwrap();
+        let csv = dir.join("table.tsv");
+        std::fs::write(&csv, "a\tb\n1\tx\n").unwrap();
+
+        let kind = |path: &Path| resolve_format(path_str(path)).unwrap().kind;
+        assert_eq!(kind(&parquet), FormatKind::Parquet);
+        assert_eq!(kind(&json), FormatKind::Json);
+        assert_eq!(kind(&ipc), FormatKind::Ipc);
+        assert_eq!(kind(&csv), FormatKind::Csv);
+
+        // Without content to sniff, the extension decides
+        let empty = dir.join("empty.JSONL");
+        std::fs::write(&empty, "").unwrap();
+        assert_eq!(kind(&empty), FormatKind::Json);
+        let unknown = dir.join("empty.xyz");
+        std::fs::write(&unknown, "").unwrap();
+        assert!(resolve_format(path_str(&unknown)).is_err());
+
+        // Directories are Parquet datasets
+        assert_eq!(kind(&dir), FormatKind::Parquet);
+    }
+
+    #[test]
+    fn files_with_glob_characters_open() {
+        let dir = temp_dir("glob-names");
+        let mut df = df!("a" => [1i64, 2], "b" => ["x", "y"]).unwrap();
+
+        let csv = dir.join("export [1].csv");
+        std::fs::write(&csv, "a,b\n1,x\n2,y\n").unwrap();
+        let json = dir.join("events?.jsonl");
+        std::fs::write(&json, "{\"a\": 1}\n{\"a\": 2}\n").unwrap();
+        let ipc = dir.join("frame*.arrow");
+        IpcWriter::new(File::create(&ipc).unwrap())
+            .finish(&mut df)
+            .unwrap();
+
+        for path in [&csv, &json, &ipc] {
+            let lf = open_file(path_str(path), &ReadOptions::default()).unwrap();
+            assert_eq!(lf.collect().unwrap().height(), 2, "{}", path.display());
+        }
+    }
+}
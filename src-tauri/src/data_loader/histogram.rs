@@ -0,0 +1,546 @@
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Number of bins or bars when none is given
+const DEFAULT_BINS: usize = 20;
+
+/// Upper bound on the number of bins chosen by a binning rule
+const MAX_BINS: usize = 1000;
+
+/// Bucket sizes tried for datetime histograms, with their approximate length
+/// in microseconds
+const TIME_BUCKETS: [(&str, i128); 9] = [
+    ("1s", 1_000_000),
+    ("1m", 60_000_000),
+    ("1h", 3_600_000_000),
+    ("1d", 86_400_000_000),
+    ("1w", 604_800_000_000),
+    ("1mo", 2_592_000_000_000),
+    ("1q", 7_776_000_000_000),
+    ("1y", 31_536_000_000_000),
+    ("10y", 315_360_000_000_000),
+];
+
+/// How numeric values are divided into bins
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Binning {
+    /// Bins of equal width
+    #[default]
+    EqualWidth,
+    /// Bin width from the interquartile range, suited to skewed data
+    FreedmanDiaconis,
+    /// log2(n) + 1 bins, suited to roughly normal data
+    Sturges,
+    /// Bins holding about the same number of values
+    Quantile,
+    /// Bin edges given in increasing order. Values outside them are excluded.
+    Edges { edges: Vec<f64> },
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HistogramOptions {
+    pub binning: Binning,
+    /// Bins of equal width in log10 space. Values that are not positive are
+    /// excluded.
+    pub log_scale: bool,
+    /// Exclude values below this percentile and above 100 minus it
+    pub clip_percentile: Option<f64>,
+    /// Bucket size of datetime histograms, e.g. "1h" or "1mo". Chosen from the
+    /// range of the column when omitted. A size that would give more than
+    /// `MAX_BINS` buckets is replaced by the smallest one that does not.
+    pub time_bucket: Option<String>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum HistogramKind {
+    /// Bins between numeric edges
+    Numeric,
+    /// Counts per time bucket
+    Temporal,
+    /// Counts per value
+    Categorical,
+}
+
+#[derive(Serialize)]
+pub struct HistogramData {
+    pub kind: HistogramKind,
+    /// Bin edges, one more than the counts. Empty for temporal and categorical
+    /// histograms.
+    pub bins: Vec<f64>,
+    pub counts: Vec<usize>,
+    /// Bar labels: the values of categorical histograms and the bucket starts
+    /// of temporal histograms. Empty for numeric histograms.
+    pub labels: Vec<String>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub null_count: usize,
+    /// Values left out of every bin: clipped, outside explicit edges, not
+    /// positive on a log scale, not finite, or beyond the most frequent categories
+    pub excluded_count: usize,
+    /// Bucket size of temporal histograms, such as `1d`. Larger than the
+    /// requested bucket when that one would give more than `MAX_BINS` buckets.
+    pub time_bucket: Option<String>,
+}
+
+/// Histogram of a column. Numeric columns are binned, datetime columns are
+/// counted per time bucket, and boolean and string columns per value.
+pub fn calculate_histogram_from_dataframe(
+    df: &DataFrame,
+    column_name: &str,
+    num_bins: Option<usize>,
+    options: &HistogramOptions,
+) -> Result<HistogramData, String> {
+    // Get the column
+    let series = df
+        .column(column_name)
+        .map_err(|e| format!("Column not found: {}", e))?
+        .as_materialized_series();
+
+    if num_bins == Some(0) {
+        return Err("Number of bins must be positive".to_string());
+    }
+
+    match series.dtype() {
+        DataType::Boolean
+        | DataType::String
+        | DataType::Categorical(_, _)
+        | DataType::Enum(_, _) => categorical_histogram(series, num_bins.unwrap_or(DEFAULT_BINS)),
+        DataType::Date | DataType::Datetime(_, _) => temporal_histogram(
+            series,
+            num_bins.unwrap_or(DEFAULT_BINS),
+            options.time_bucket.as_deref(),
+        ),
+        _ => numeric_histogram(series, num_bins, options),
+    }
+}
+
+fn numeric_histogram(
+    series: &Series,
+    num_bins: Option<usize>,
+    options: &HistogramOptions,
+) -> Result<HistogramData, String> {
+    // Convert to f64
+    let ca = series
+        .cast(&DataType::Float64)
+        .map_err(|e| format!("Failed to convert to numeric: {}", e))?;
+    let f64_series = ca
+        .f64()
+        .map_err(|e| format!("Failed to get f64 series: {}", e))?;
+
+    let null_count = f64_series.null_count();
+    let non_null = f64_series.len() - null_count;
+
+    // Values to bin, in log10 space on a log scale
+    let mut values: Vec<f64> = f64_series
+        .into_iter()
+        .flatten()
+        .filter(|v| v.is_finite() && (!options.log_scale || *v > 0.0))
+        .map(|v| if options.log_scale { v.log10() } else { v })
+        .collect();
+    values.sort_by(|a, b| a.total_cmp(b));
+
+    // Outliers beyond the percentiles
+    if let Some(percentile) = options.clip_percentile {
+        if !(0.0..50.0).contains(&percentile) {
+            return Err(format!(
+                "Clip percentile must be between 0 and 50, got {}",
+                percentile
+            ));
+        }
+        if let (Some(low), Some(high)) = (
+            quantile(&values, percentile / 100.0),
+            quantile(&values, 1.0 - percentile / 100.0),
+        ) {
+            values.retain(|v| *v >= low && *v <= high);
+        }
+    }
+
+    let mut edges = match &options.binning {
+        Binning::Edges { edges } => {
+            if edges.len() < 2 || edges.windows(2).any(|pair| pair[0] >= pair[1]) {
+                return Err("Bin edges must be at least two increasing values".to_string());
+            }
+            if options.log_scale && edges[0] <= 0.0 {
+                return Err("Bin edges must be positive on a log scale".to_string());
+            }
+            edges
+                .iter()
+                .map(|e| if options.log_scale { e.log10() } else { *e })
+                .collect()
+        }
+        binning => {
+            let (Some(&min), Some(&max)) = (values.first(), values.last()) else {
+                return Err("No values to bin in column".to_string());
+            };
+            bin_edges(binning, &values, min, max, num_bins)
+        }
+    };
+
+    // Count values in each bin
+    let mut counts = vec![0usize; edges.len() - 1];
+    for &value in &values {
+        if let Some(bin_idx) = bin_index(&edges, value) {
+            counts[bin_idx] += 1;
+        }
+    }
+    let excluded_count = non_null - counts.iter().sum::<usize>();
+
+    if options.log_scale {
+        for edge in edges.iter_mut() {
+            *edge = 10f64.powf(*edge);
+        }
+    }
+
+    Ok(HistogramData {
+        kind: HistogramKind::Numeric,
+        min: edges.first().copied(),
+        max: edges.last().copied(),
+        bins: edges,
+        counts,
+        labels: Vec::new(),
+        null_count,
+        excluded_count,
+        time_bucket: None,
+    })
+}
+
+/// Bin of a value, or `None` if it is outside the edges. The last bin
+/// includes its upper edge.
+pub(super) fn bin_index(edges: &[f64], value: f64) -> Option<usize> {
+    let (first, last) = (edges[0], edges[edges.len() - 1]);
+    if value < first || value > last {
+        return None;
+    }
+    let bin_idx = edges.partition_point(|edge| *edge <= value) - 1;
+    Some(bin_idx.min(edges.len() - 2))
+}
+
+/// Bin edges over sorted values between min and max
+pub(super) fn bin_edges(
+    binning: &Binning,
+    values: &[f64],
+    min: f64,
+    max: f64,
+    num_bins: Option<usize>,
+) -> Vec<f64> {
+    // All values are the same, create a single bin
+    if (max - min).abs() < f64::EPSILON {
+        return vec![min, min];
+    }
+
+    let sturges = (values.len() as f64).log2().ceil() as usize + 1;
+    let bins_count = match binning {
+        Binning::Sturges => sturges,
+        Binning::FreedmanDiaconis => {
+            let iqr = quantile(values, 0.75).unwrap_or(max) - quantile(values, 0.25).unwrap_or(min);
+            let width = 2.0 * iqr / (values.len() as f64).cbrt();
+            if width > 0.0 {
+                ((max - min) / width).ceil() as usize
+            } else {
+                sturges
+            }
+        }
+        _ => num_bins.unwrap_or(DEFAULT_BINS),
+    }
+    .clamp(1, MAX_BINS);
+
+    if *binning == Binning::Quantile {
+        let mut edges: Vec<f64> = (0..=bins_count)
+            .filter_map(|i| quantile(values, i as f64 / bins_count as f64))
+            .collect();
+        // Repeated values can give equal quantiles
+        edges.dedup();
+        if edges.len() >= 2 {
+            return edges;
+        }
+    }
+
+    let bin_width = (max - min) / bins_count as f64;
+    (0..=bins_count)
+        .map(|i| min + i as f64 * bin_width)
+        .collect()
+}
+
+/// Linearly interpolated quantile of sorted values
+fn quantile(sorted: &[f64], q: f64) -> Option<f64> {
+    let last = sorted.len().checked_sub(1)?;
+    let position = q * last as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    let fraction = position - lower as f64;
+    Some(sorted[lower] + (sorted[upper] - sorted[lower]) * fraction)
+}
+
+/// Counts of the most frequent values, ties broken by value
+fn categorical_histogram(series: &Series, limit: usize) -> Result<HistogramData, String> {
+    let null_count = series.null_count();
+    let name = series.name().clone();
+
+    let counts = DataFrame::new(vec![series
+        .cast(&DataType::String)
+        .map_err(|e| format!("Failed to convert to text: {}", e))?
+        .into()])
+    .map_err(|e| format!("Failed to count values: {}", e))?
+    .lazy()
+    .drop_nulls(None)
+    .group_by([col(name.clone())])
+    .agg([len().alias("__count")])
+    .sort_by_exprs(
+        [col("__count"), col(name.clone())],
+        SortMultipleOptions::default().with_order_descending_multi([true, false]),
+    )
+    .collect()
+    .map_err(|e| format!("Failed to count values: {}", e))?;
+
+    let labels: Vec<String> = counts
+        .column(&name)
+        .and_then(|c| c.str().cloned())
+        .map_err(|e| format!("Failed to count values: {}", e))?
+        .into_iter()
+        .take(limit)
+        .map(|value| value.unwrap_or_default().to_string())
+        .collect();
+    let all_counts: Vec<usize> = counts
+        .column("__count")
+        .and_then(|c| c.cast(&DataType::UInt64))
+        .and_then(|c| c.u64().cloned())
+        .map_err(|e| format!("Failed to count values: {}", e))?
+        .into_no_null_iter()
+        .map(|count| count as usize)
+        .collect();
+
+    let shown = labels.len();
+    Ok(HistogramData {
+        kind: HistogramKind::Categorical,
+        bins: Vec::new(),
+        counts: all_counts[..shown].to_vec(),
+        labels,
+        min: None,
+        max: None,
+        null_count,
+        excluded_count: all_counts[shown..].iter().sum(),
+        time_bucket: None,
+    })
+}
+
+/// Counts per time bucket of a Date or Datetime column, in its time zone
+fn temporal_histogram(
+    series: &Series,
+    max_buckets: usize,
+    time_bucket: Option<&str>,
+) -> Result<HistogramData, String> {
+    let null_count = series.null_count();
+    let name = series.name().clone();
+
+    let every = match time_bucket {
+        Some(every) => {
+            let duration = polars::prelude::Duration::try_parse(every)
+                .map_err(|e| format!("Invalid time bucket {}: {}", every, e))?;
+            if duration.negative() || duration.is_zero() {
+                return Err(format!("Time bucket must be positive: {}", every));
+            }
+            let too_many = time_range_micros(series)
+                .is_some_and(|range| range / duration_micros(&duration) >= MAX_BINS as i128);
+            if too_many {
+                auto_time_bucket(series, MAX_BINS).to_string()
+            } else {
+                every.to_string()
+            }
+        }
+        None => auto_time_bucket(series, max_buckets).to_string(),
+    };
+
+    let buckets = DataFrame::new(vec![series.clone().into()])
+        .map_err(|e| format!("Failed to bucket values: {}", e))?
+        .lazy()
+        .drop_nulls(None)
+        .group_by([col(name.clone()).dt().truncate(lit(every.clone()))])
+        .agg([len().alias("__count")])
+        .sort([name.clone()], SortMultipleOptions::default())
+        .collect()
+        .map_err(|e| format!("Failed to bucket values: {}", e))?;
+
+    let labels: Vec<String> = buckets
+        .column(&name)
+        .and_then(|c| c.cast(&DataType::String))
+        .and_then(|c| c.str().cloned())
+        .map_err(|e| format!("Failed to bucket values: {}", e))?
+        .into_iter()
+        .map(|value| value.unwrap_or_default().to_string())
+        .collect();
+    let counts: Vec<usize> = buckets
+        .column("__count")
+        .and_then(|c| c.cast(&DataType::UInt64))
+        .and_then(|c| c.u64().cloned())
+        .map_err(|e| format!("Failed to bucket values: {}", e))?
+        .into_no_null_iter()
+        .map(|count| count as usize)
+        .collect();
+
+    Ok(HistogramData {
+        kind: HistogramKind::Temporal,
+        bins: Vec::new(),
+        counts,
+        labels,
+        min: None,
+        max: None,
+        null_count,
+        excluded_count: 0,
+        time_bucket: Some(every),
+    })
+}
+
+/// Smallest bucket size giving at most `max_buckets` buckets
+fn auto_time_bucket(series: &Series, max_buckets: usize) -> &'static str {
+    let Some(range) = time_range_micros(series) else {
+        return TIME_BUCKETS[0].0;
+    };
+    TIME_BUCKETS
+        .iter()
+        .find(|(_, size)| range / size < max_buckets as i128)
+        .unwrap_or(&TIME_BUCKETS[TIME_BUCKETS.len() - 1])
+        .0
+}
+
+/// Range of a Date or Datetime column in microseconds. Dates far from 1970
+/// and millisecond timestamps overflow i64 microseconds, so it is an i128.
+fn time_range_micros(series: &Series) -> Option<i128> {
+    let micros = |value: Option<i64>| -> Option<i128> {
+        let value = i128::from(value?);
+        Some(match series.dtype() {
+            DataType::Date => value * 86_400_000_000,
+            DataType::Datetime(TimeUnit::Nanoseconds, _) => value / 1_000,
+            DataType::Datetime(TimeUnit::Milliseconds, _) => value * 1_000,
+            _ => value,
+        })
+    };
+
+    let physical = series.to_physical_repr().cast(&DataType::Int64).ok()?;
+    let values = physical.i64().ok()?;
+    Some(micros(values.max())? - micros(values.min())?)
+}
+
+/// Approximate length of a bucket size in microseconds, with months of 30
+/// days like `TIME_BUCKETS`. At least 1, for sizes below a microsecond.
+fn duration_micros(duration: &polars::prelude::Duration) -> i128 {
+    let micros = i128::from(duration.months()) * 2_592_000_000_000
+        + i128::from(duration.weeks()) * 604_800_000_000
+        + i128::from(duration.days()) * 86_400_000_000
+        + i128::from(duration.nanoseconds()) / 1_000;
+    micros.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temporal(values: &[i64], dtype: DataType, time_bucket: Option<&str>) -> HistogramData {
+        let series = Series::new("t".into(), values).cast(&dtype).unwrap();
+        let df = DataFrame::new(vec![series.into()]).unwrap();
+        let options = HistogramOptions {
+            time_bucket: time_bucket.map(str::to_string),
+            ..Default::default()
+        };
+        calculate_histogram_from_dataframe(&df, "t", None, &options).unwrap()
+    }
+
+    #[test]
+    fn values_fall_in_half_open_bins_except_the_last() {
+        let edges = [0.0, 1.0, 2.0, 4.0];
+        assert_eq!(bin_index(&edges, 0.0), Some(0));
+        assert_eq!(bin_index(&edges, 0.999), Some(0));
+        assert_eq!(bin_index(&edges, 1.0), Some(1));
+        assert_eq!(bin_index(&edges, 3.0), Some(2));
+        assert_eq!(bin_index(&edges, 4.0), Some(2));
+        assert_eq!(bin_index(&edges, -0.1), None);
+        assert_eq!(bin_index(&edges, 4.1), None);
+
+        // A column of one repeated value has a single bin
+        assert_eq!(bin_index(&[5.0, 5.0], 5.0), Some(0));
+    }
+
+    #[test]
+    fn bin_edges_follow_the_binning() {
+        let values: Vec<f64> = (0..=100).map(f64::from).collect();
+
+        let edges = bin_edges(&Binning::EqualWidth, &values, 0.0, 100.0, Some(4));
+        assert_eq!(edges, vec![0.0, 25.0, 50.0, 75.0, 100.0]);
+
+        // log2(101) + 1 rounded up
+        let edges = bin_edges(&Binning::Sturges, &values, 0.0, 100.0, None);
+        assert_eq!(edges.len(), 9);
+
+        let edges = bin_edges(&Binning::Quantile, &values, 0.0, 100.0, Some(2));
+        assert_eq!(edges, vec![0.0, 50.0, 100.0]);
+
+        // Repeated values give equal quantiles, which are merged
+        let skewed = [1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 2.0, 3.0];
+        let edges = bin_edges(&Binning::Quantile, &skewed, 1.0, 3.0, Some(4));
+        assert_eq!(edges.first(), Some(&1.0));
+        assert!(edges.windows(2).all(|pair| pair[0] < pair[1]));
+
+        assert_eq!(
+            bin_edges(&Binning::EqualWidth, &[2.0, 2.0], 2.0, 2.0, None),
+            vec![2.0, 2.0]
+        );
+    }
+
+    #[test]
+    fn explicit_time_buckets_are_capped() {
+        let millis = DataType::Datetime(TimeUnit::Milliseconds, None);
+        let year = [0, 365 * 86_400_000];
+
+        let days = temporal(&year, millis.clone(), Some("1d"));
+        assert_eq!(days.counts, vec![1, 1]);
+        assert_eq!(days.labels[1], "1971-01-01 00:00:00.000");
+
+        // A second per bucket would give millions of buckets, so the smallest
+        // size giving at most MAX_BINS is used instead
+        let seconds = temporal(&year, millis.clone(), Some("1s"));
+        assert_eq!(seconds.labels, days.labels);
+        assert_eq!(seconds.time_bucket.as_deref(), Some("1d"));
+        assert_eq!(days.time_bucket.as_deref(), Some("1d"));
+        let weeks = temporal(&year, millis.clone(), Some("1w"));
+        assert_eq!(weeks.time_bucket.as_deref(), Some("1w"));
+
+        let year = Series::new("t".into(), year).cast(&millis).unwrap();
+        assert_eq!(auto_time_bucket(&year, MAX_BINS), "1d");
+        assert_eq!(auto_time_bucket(&year, DEFAULT_BINS), "1mo");
+    }
+
+    #[test]
+    fn time_ranges_far_from_1970_do_not_overflow() {
+        let millis = DataType::Datetime(TimeUnit::Milliseconds, None);
+        let far = [-8_000_000_000_000_000i64, 8_000_000_000_000_000];
+        assert_eq!(
+            auto_time_bucket(&Series::new("t".into(), far).cast(&millis).unwrap(), 20),
+            "10y"
+        );
+
+        let dates = Series::new("d".into(), [-90_000_000i32, 90_000_000])
+            .cast(&DataType::Date)
+            .unwrap();
+        assert_eq!(
+            time_range_micros(&dates),
+            Some(180_000_000 * 86_400_000_000)
+        );
+    }
+
+    #[test]
+    fn invalid_time_buckets_are_rejected() {
+        let series = Series::new("t".into(), [0i64])
+            .cast(&DataType::Date)
+            .unwrap();
+        let df = DataFrame::new(vec![series.into()]).unwrap();
+        for time_bucket in ["often", "0d"] {
+            let options = HistogramOptions {
+                time_bucket: Some(time_bucket.to_string()),
+                ..Default::default()
+            };
+            assert!(calculate_histogram_from_dataframe(&df, "t", None, &options).is_err());
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use polars::prelude::*;
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+use super::format_registry::scan_path;
+
+/// Arrow IPC file format (Feather v2) magic bytes
+const IPC_FILE_MAGIC: &[u8] = b"ARROW1";
+/// Arrow IPC stream messages start with a continuation marker
+const IPC_STREAM_CONTINUATION: &[u8] = &[0xFF, 0xFF, 0xFF, 0xFF];
+/// Legacy Feather v1 magic bytes
+const FEATHER_V1_MAGIC: &[u8] = b"FEA1";
+
+/// Opens an Arrow IPC / Feather file and returns a LazyFrame.
+///
+/// The IPC file format has a footer with record batch offsets, so it is
+/// scanned lazily and Polars memory-maps it: only the buffers a query touches
+/// are paged in. Uncompressed columns stay backed by the map after collecting,
+/// which keeps the file mapped for as long as the DataFrame is cached.
+///
+/// The IPC stream format has no footer and cannot be mapped or sliced, so it
+/// is read into memory in one pass.
+pub fn open_ipc(file_path: &str) -> Result<LazyFrame, String> {
+    let mut file = File::open(file_path).map_err(|e| format!("Failed to open file: {}", e))?;
+
+    let mut magic = [0u8; 6];
+    let read = file
+        .read(&mut magic)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    let magic = &magic[..read];
+
+    if magic.starts_with(IPC_FILE_MAGIC) {
+        return LazyFrame::scan_ipc(
+            scan_path(file_path),
+            IpcScanOptions,
+            UnifiedScanArgs::default(),
+        )
+        .map_err(|e| format!("Failed to scan Arrow IPC file: {}", e));
+    }
+
+    if magic.starts_with(FEATHER_V1_MAGIC) {
+        return Err(
+            "Feather v1 files are not supported. Please re-export the data as Feather v2 (Arrow IPC)."
+                .to_string(),
+        );
+    }
+
+    let file = File::open(file_path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let df = IpcStreamReader::new(BufReader::new(file))
+        .finish()
+        .map_err(|e| format!("Failed to read Arrow IPC stream: {}", e))?;
+
+    Ok(df.lazy())
+}
+
+/// Arrow IPC files start with `ARROW1`, streams with a continuation marker
+pub fn sniff_ipc(bytes: &[u8]) -> bool {
+    bytes.starts_with(IPC_FILE_MAGIC)
+        || bytes.starts_with(IPC_STREAM_CONTINUATION)
+        || bytes.starts_with(FEATHER_V1_MAGIC)
+}
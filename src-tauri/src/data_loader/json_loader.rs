@@ -0,0 +1,57 @@
+use polars::prelude::*;
+use std::fs::File;
+use std::io::Read;
+use std::num::NonZeroUsize;
+
+use super::format_registry::scan_path;
+
+/// Number of records used to infer the schema
+const INFER_SCHEMA_LENGTH: usize = 10000;
+
+/// Opens a JSON file and returns a LazyFrame.
+///
+/// Newline-delimited JSON is scanned lazily. A JSON array has no streaming
+/// reader in Polars, so it is read eagerly and wrapped in a LazyFrame.
+pub fn open_json(file_path: &str) -> Result<LazyFrame, String> {
+    let infer_schema_length = NonZeroUsize::new(INFER_SCHEMA_LENGTH);
+
+    if is_json_array(file_path)? {
+        let file = File::open(file_path).map_err(|e| format!("Failed to open file: {}", e))?;
+
+        let df = JsonReader::new(file)
+            .with_json_format(JsonFormat::Json)
+            .infer_schema_len(infer_schema_length)
+            .finish()
+            .map_err(|e| format!("Failed to read JSON file: {}", e))?;
+
+        return Ok(df.lazy());
+    }
+
+    LazyJsonLineReader::new(scan_path(file_path))
+        .with_infer_schema_length(infer_schema_length)
+        .finish()
+        .map_err(|e| format!("Failed to scan JSON file: {}", e))
+}
+
+/// JSON files start with an object (newline-delimited) or an array
+pub fn sniff_json(bytes: &[u8]) -> bool {
+    matches!(first_significant_byte(bytes), Some(b'{') | Some(b'['))
+}
+
+fn is_json_array(file_path: &str) -> Result<bool, String> {
+    let mut file = File::open(file_path).map_err(|e| format!("Failed to open file: {}", e))?;
+
+    // Leading whitespace is unbounded in theory, but a small prefix is plenty in practice
+    let mut buffer = [0u8; 1024];
+    let read = file
+        .read(&mut buffer)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    Ok(first_significant_byte(&buffer[..read]) == Some(b'['))
+}
+
+/// First byte after an optional UTF-8 BOM and leading whitespace
+fn first_significant_byte(bytes: &[u8]) -> Option<u8> {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    bytes.iter().copied().find(|b| !b.is_ascii_whitespace())
+}
@@ -1,7 +1,36 @@
+pub mod correlation;
 pub mod csv_loader;
 pub mod dataframe_processor;
+pub mod format_registry;
+pub mod histogram;
+pub mod ipc_loader;
+pub mod json_loader;
+pub mod page_header;
 pub mod parquet_loader;
+pub mod parquet_metadata;
+pub mod pruning;
+pub mod row_groups;
+pub mod schema_tree;
 
-pub use csv_loader::open_csv;
-pub use dataframe_processor::{apply_sorts, calculate_statistics, dataframe_to_rows, Sorting};
-pub use parquet_loader::open_parquet;
+#[cfg(test)]
+pub(crate) mod test_utils;
+
+pub use correlation::{
+    calculate_correlations, calculate_pair_density, select_numeric, CorrelationMatrix,
+    CorrelationMethod, PairDensity,
+};
+pub use csv_loader::CsvOptions;
+pub use dataframe_processor::{
+    apply_filters, apply_sorts, calculate_statistics_lazy, count_rows_and_nulls, dataframe_to_rows,
+    Filter, Sorting,
+};
+pub use format_registry::{open_file, resolve_format, FormatKind, ReadOptions};
+pub use histogram::{calculate_histogram_from_dataframe, HistogramData, HistogramOptions};
+pub use parquet_loader::{dataset_files, dataset_root, footer_counts, view_footer_statistics};
+pub use parquet_metadata::{column_pages, parquet_footers, ColumnPages, ParquetFooter};
+pub use pruning::{row_group_pruning, PruningReport};
+pub use row_groups::{
+    locate_row_group, row_group_offsets, row_group_range, row_groups, RowGroupOffset,
+    RowGroupRange, RowLocation,
+};
+pub use schema_tree::{schema_tree, SchemaNode};
@@ -0,0 +1,342 @@
+use std::io::{self, Read};
+
+/// Header of one page of a column chunk, read from the Thrift compact
+/// encoding in front of the page data. Only the fields shown in the page
+/// browser are kept; the rest are skipped.
+#[derive(Debug, Default)]
+pub struct PageHeader {
+    pub page_type: i32,
+    pub uncompressed_size: i32,
+    pub compressed_size: i32,
+    pub num_values: Option<i32>,
+    pub encoding: Option<i32>,
+    /// Only recorded in v2 data pages
+    pub num_rows: Option<i32>,
+    pub num_nulls: Option<i32>,
+    pub statistics: Option<PageStatistics>,
+}
+
+/// Statistics of a data page, with min and max in their plain encoding
+#[derive(Debug, Default)]
+pub struct PageStatistics {
+    pub min: Option<Vec<u8>>,
+    pub max: Option<Vec<u8>>,
+    pub null_count: Option<i64>,
+    pub distinct_count: Option<i64>,
+    pub min_is_exact: Option<bool>,
+    pub max_is_exact: Option<bool>,
+}
+
+/// Name of a page type, as written by the parquet crate
+pub fn page_type_name(page_type: i32) -> String {
+    match page_type {
+        0 => "DATA_PAGE".to_string(),
+        1 => "INDEX_PAGE".to_string(),
+        2 => "DICTIONARY_PAGE".to_string(),
+        3 => "DATA_PAGE_V2".to_string(),
+        other => format!("UNKNOWN({})", other),
+    }
+}
+
+/// Name of an encoding, as written by the parquet crate
+pub fn encoding_name(encoding: i32) -> String {
+    match encoding {
+        0 => "PLAIN".to_string(),
+        2 => "PLAIN_DICTIONARY".to_string(),
+        3 => "RLE".to_string(),
+        4 => "BIT_PACKED".to_string(),
+        5 => "DELTA_BINARY_PACKED".to_string(),
+        6 => "DELTA_LENGTH_BYTE_ARRAY".to_string(),
+        7 => "DELTA_BYTE_ARRAY".to_string(),
+        8 => "RLE_DICTIONARY".to_string(),
+        9 => "BYTE_STREAM_SPLIT".to_string(),
+        other => format!("UNKNOWN({})", other),
+    }
+}
+
+/// Read a page header and return it with its length in bytes
+pub fn read_page_header<R: Read>(input: R) -> io::Result<(PageHeader, usize)> {
+    let mut reader = CompactReader {
+        input,
+        bytes_read: 0,
+    };
+    let mut header = PageHeader::default();
+
+    reader.read_struct(|reader, field, field_type| {
+        match (field, field_type) {
+            (1, I32) => header.page_type = reader.read_i32()?,
+            (2, I32) => header.uncompressed_size = reader.read_i32()?,
+            (3, I32) => header.compressed_size = reader.read_i32()?,
+            // Data page
+            (5, STRUCT) => reader.read_struct(|reader, field, field_type| {
+                match (field, field_type) {
+                    (1, I32) => header.num_values = Some(reader.read_i32()?),
+                    (2, I32) => header.encoding = Some(reader.read_i32()?),
+                    (5, STRUCT) => header.statistics = Some(reader.read_statistics()?),
+                    _ => reader.skip(field_type)?,
+                }
+                Ok(())
+            })?,
+            // Dictionary page
+            (7, STRUCT) => reader.read_struct(|reader, field, field_type| {
+                match (field, field_type) {
+                    (1, I32) => header.num_values = Some(reader.read_i32()?),
+                    (2, I32) => header.encoding = Some(reader.read_i32()?),
+                    _ => reader.skip(field_type)?,
+                }
+                Ok(())
+            })?,
+            // Data page v2
+            (8, STRUCT) => reader.read_struct(|reader, field, field_type| {
+                match (field, field_type) {
+                    (1, I32) => header.num_values = Some(reader.read_i32()?),
+                    (2, I32) => header.num_nulls = Some(reader.read_i32()?),
+                    (3, I32) => header.num_rows = Some(reader.read_i32()?),
+                    (4, I32) => header.encoding = Some(reader.read_i32()?),
+                    (8, STRUCT) => header.statistics = Some(reader.read_statistics()?),
+                    _ => reader.skip(field_type)?,
+                }
+                Ok(())
+            })?,
+            _ => reader.skip(field_type)?,
+        }
+        Ok(())
+    })?;
+
+    Ok((header, reader.bytes_read))
+}
+
+// Field types of the Thrift compact protocol
+const BOOLEAN_TRUE: u8 = 1;
+const BOOLEAN_FALSE: u8 = 2;
+const BYTE: u8 = 3;
+const I16: u8 = 4;
+const I32: u8 = 5;
+const I64: u8 = 6;
+const DOUBLE: u8 = 7;
+const BINARY: u8 = 8;
+const LIST: u8 = 9;
+const SET: u8 = 10;
+const MAP: u8 = 11;
+const STRUCT: u8 = 12;
+
+/// Nesting limit, so a corrupt header cannot recurse without end
+const MAX_DEPTH: usize = 32;
+
+struct CompactReader<R> {
+    input: R,
+    bytes_read: usize,
+}
+
+impl<R: Read> CompactReader<R> {
+    fn read_byte(&mut self) -> io::Result<u8> {
+        let mut byte = [0u8];
+        self.input.read_exact(&mut byte)?;
+        self.bytes_read += 1;
+        Ok(byte[0])
+    }
+
+    fn read_varint(&mut self) -> io::Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_byte()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("varint is too long"))
+    }
+
+    fn read_i64(&mut self) -> io::Result<i64> {
+        let value = self.read_varint()?;
+        Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+
+    fn read_i32(&mut self) -> io::Result<i32> {
+        i32::try_from(self.read_i64()?).map_err(|_| invalid("i32 is out of range"))
+    }
+
+    fn read_binary(&mut self) -> io::Result<Vec<u8>> {
+        let len = self.read_varint()?;
+        let mut bytes = Vec::new();
+        (&mut self.input).take(len).read_to_end(&mut bytes)?;
+        if bytes.len() as u64 != len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        self.bytes_read += bytes.len();
+        Ok(bytes)
+    }
+
+    /// Read the fields of a struct up to its stop byte, passing each field id
+    /// and type to `field`
+    fn read_struct(
+        &mut self,
+        mut field: impl FnMut(&mut Self, i16, u8) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let mut id = 0i16;
+        loop {
+            let byte = self.read_byte()?;
+            if byte == 0 {
+                return Ok(());
+            }
+            let delta = (byte >> 4) as i16;
+            id = if delta == 0 {
+                i16::try_from(self.read_i64()?).map_err(|_| invalid("field id is out of range"))?
+            } else {
+                id + delta
+            };
+            field(self, id, byte & 0x0f)?;
+        }
+    }
+
+    fn read_statistics(&mut self) -> io::Result<PageStatistics> {
+        let mut statistics = PageStatistics::default();
+        // Fields 1 and 2 are the deprecated max and min, used when the newer
+        // fields are missing
+        let (mut old_min, mut old_max) = (None, None);
+        self.read_struct(|reader, field, field_type| {
+            match (field, field_type) {
+                (1, BINARY) => old_max = Some(reader.read_binary()?),
+                (2, BINARY) => old_min = Some(reader.read_binary()?),
+                (3, I64) => statistics.null_count = Some(reader.read_i64()?),
+                (4, I64) => statistics.distinct_count = Some(reader.read_i64()?),
+                (5, BINARY) => statistics.max = Some(reader.read_binary()?),
+                (6, BINARY) => statistics.min = Some(reader.read_binary()?),
+                (7, BOOLEAN_TRUE | BOOLEAN_FALSE) => {
+                    statistics.max_is_exact = Some(field_type == BOOLEAN_TRUE)
+                }
+                (8, BOOLEAN_TRUE | BOOLEAN_FALSE) => {
+                    statistics.min_is_exact = Some(field_type == BOOLEAN_TRUE)
+                }
+                _ => reader.skip(field_type)?,
+            }
+            Ok(())
+        })?;
+        statistics.min = statistics.min.or(old_min);
+        statistics.max = statistics.max.or(old_max);
+        Ok(statistics)
+    }
+
+    fn skip(&mut self, field_type: u8) -> io::Result<()> {
+        self.skip_nested(field_type, 0)
+    }
+
+    /// Skip an element of a list, set or map, where booleans take a byte
+    fn skip_element(&mut self, element_type: u8, depth: usize) -> io::Result<()> {
+        match element_type {
+            BOOLEAN_TRUE | BOOLEAN_FALSE => self.read_byte().map(|_| ()),
+            _ => self.skip_nested(element_type, depth),
+        }
+    }
+
+    fn skip_nested(&mut self, field_type: u8, depth: usize) -> io::Result<()> {
+        if depth > MAX_DEPTH {
+            return Err(invalid("page header is nested too deeply"));
+        }
+        match field_type {
+            // Booleans of struct fields are stored in the field type
+            BOOLEAN_TRUE | BOOLEAN_FALSE => {}
+            BYTE => {
+                self.read_byte()?;
+            }
+            I16 | I32 | I64 => {
+                self.read_varint()?;
+            }
+            DOUBLE => {
+                for _ in 0..8 {
+                    self.read_byte()?;
+                }
+            }
+            BINARY => {
+                self.read_binary()?;
+            }
+            LIST | SET => {
+                let header = self.read_byte()?;
+                let len = match header >> 4 {
+                    15 => self.read_varint()?,
+                    len => u64::from(len),
+                };
+                for _ in 0..len {
+                    self.skip_element(header & 0x0f, depth + 1)?;
+                }
+            }
+            MAP => {
+                let len = self.read_varint()?;
+                if len > 0 {
+                    let types = self.read_byte()?;
+                    for _ in 0..len {
+                        self.skip_element(types >> 4, depth + 1)?;
+                        self.skip_element(types & 0x0f, depth + 1)?;
+                    }
+                }
+            }
+            STRUCT => loop {
+                let byte = self.read_byte()?;
+                if byte == 0 {
+                    break;
+                }
+                // A field id that is not a delta of the previous one follows the byte
+                if byte >> 4 == 0 {
+                    self.read_varint()?;
+                }
+                self.skip_nested(byte & 0x0f, depth + 1)?;
+            },
+            other => return Err(invalid(&format!("unknown field type {}", other))),
+        }
+        Ok(())
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_v2_data_page_header() {
+        #[rustfmt::skip]
+        let bytes = [
+            0x15, 0x06, // type: DATA_PAGE_V2
+            0x15, 0xc8, 0x01, // uncompressed_page_size: 100
+            0x15, 0x64, // compressed_page_size: 50
+            0x15, 0x0e, // crc, skipped
+            0x4c, // data_page_header_v2
+            0x15, 0x14, 0x15, 0x04, 0x15, 0x14, 0x15, 0x10, 0x15, 0x00, 0x15, 0x00, 0x11,
+            0x1c, // statistics
+            0x36, 0x04, 0x28, 0x01, b'z', 0x18, 0x01, b'a', 0x21, 0x00,
+            0x00,
+            0x00,
+            // Page data
+            0xff, 0xff,
+        ];
+
+        let (header, size) = read_page_header(&bytes[..]).unwrap();
+        assert_eq!(size, bytes.len() - 2);
+        assert_eq!(page_type_name(header.page_type), "DATA_PAGE_V2");
+        assert_eq!(header.uncompressed_size, 100);
+        assert_eq!(header.compressed_size, 50);
+        assert_eq!(header.num_values, Some(10));
+        assert_eq!(header.num_nulls, Some(2));
+        assert_eq!(header.num_rows, Some(10));
+        assert_eq!(
+            header.encoding.map(encoding_name).as_deref(),
+            Some("RLE_DICTIONARY")
+        );
+
+        let statistics = header.statistics.unwrap();
+        assert_eq!(statistics.null_count, Some(2));
+        assert_eq!(statistics.min.as_deref(), Some(&b"a"[..]));
+        assert_eq!(statistics.max.as_deref(), Some(&b"z"[..]));
+        assert_eq!(statistics.min_is_exact, Some(true));
+        assert_eq!(statistics.max_is_exact, None);
+    }
+
+    #[test]
+    fn truncated_header_is_an_error() {
+        assert!(read_page_header(&[0x15, 0x06, 0x15][..]).is_err());
+    }
+}
@@ -1,40 +1,478 @@
-use polars::prelude::*;
-use std::fs::File;
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData};
 use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::statistics::Statistics;
+use polars::io::HiveOptions;
+use polars::prelude::*;
+use std::collections::HashMap;
 
-/// Opens a Parquet file and returns a LazyFrame
+use super::dataframe_processor::Filter;
+use super::format_registry::{resolve_format, scan_path, FormatKind};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// Opens a Parquet file, directory or glob of Parquet files and returns a LazyFrame.
+///
+/// Directories and globs are scanned as one dataset, with hive-style
+/// `key=value` path segments turned into columns.
 pub fn open_parquet(file_path: &str) -> Result<LazyFrame, String> {
-    // Pre-check the schema for unsupported types
-    check_schema_compatibility(file_path)?;
+    // Fail early with a clear message if a directory or glob is empty
+    let files = dataset_files(file_path)?;
+
+    let lf = if is_parquet_dataset(file_path) {
+        // Scan the listed files only; Polars would also read the marker and
+        // checksum files next to them
+        let paths = files
+            .iter()
+            .map(|file| PlPath::from_string(file.to_string_lossy().into_owned()))
+            .collect();
+        let args = ScanArgsParquet {
+            hive_options: HiveOptions::new_enabled(),
+            glob: false,
+            ..Default::default()
+        };
+        LazyFrame::scan_parquet_files(paths, args)
+    } else {
+        LazyFrame::scan_parquet(scan_path(file_path), Default::default())
+    };
+
+    lf.map_err(|e| format!("Failed to scan Parquet file: {}", e))
+}
+
+/// Parquet files start with the `PAR1` magic bytes
+pub fn sniff_parquet(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"PAR1")
+}
+
+/// Returns true if the path names a multi-file dataset (a directory or a glob)
+pub fn is_parquet_dataset(file_path: &str) -> bool {
+    is_glob(file_path) || Path::new(file_path).is_dir()
+}
+
+/// List the files behind a Parquet path: the file itself, every data file
+/// below a directory, or every file matching a glob. Hidden files and
+/// `_`-prefixed marker files (e.g. `_SUCCESS`) are skipped, and so are files
+/// that are not Parquet, such as checksums or a README.
+pub fn dataset_files(file_path: &str) -> Result<Vec<PathBuf>, String> {
+    let mut files = if is_glob(file_path) {
+        glob::glob(file_path)
+            .map_err(|e| format!("Invalid glob pattern: {}", e))?
+            .filter_map(Result::ok)
+            .filter(|path| path.is_file() && !is_hidden_file(path) && is_parquet_file(path))
+            .collect()
+    } else if Path::new(file_path).is_dir() {
+        let mut files = Vec::new();
+        collect_files(Path::new(file_path), &mut files)?;
+        files
+    } else {
+        return Ok(vec![PathBuf::from(file_path)]);
+    };
+
+    if files.is_empty() {
+        return Err(format!("No Parquet files found in {}", file_path));
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Row and null counts taken from the Parquet footers without reading any data
+pub struct FooterCounts {
+    pub rows: usize,
+    /// `None` if a column chunk has no null count statistic, or the schema is
+    /// nested (leaf null counts do not match top-level column null counts)
+    pub nulls: Option<usize>,
+}
+
+/// Sum the footer row and null counts over every file of a Parquet path
+pub fn footer_counts(file_path: &str) -> Result<FooterCounts, String> {
+    let mut rows = 0;
+    let mut nulls = Some(0);
+
+    for file in dataset_files(file_path)? {
+        let metadata = read_parquet_metadata(&file)?;
+        rows += metadata.file_metadata().num_rows() as usize;
+
+        let schema = metadata.file_metadata().schema_descr();
+        let is_flat = schema
+            .root_schema()
+            .get_fields()
+            .iter()
+            .all(|field| field.is_primitive());
+        if !is_flat {
+            nulls = None;
+        }
+
+        for row_group in metadata.row_groups() {
+            for column in row_group.columns() {
+                let chunk_nulls = column.statistics().and_then(|s| s.null_count_opt());
+                nulls = nulls.zip(chunk_nulls).map(|(n, c)| n + c as usize);
+            }
+        }
+    }
+
+    Ok(FooterCounts { rows, nulls })
+}
+
+/// Null counts of the top-level columns of a Parquet path, and min/max of its
+/// numeric columns, aggregated from the row group statistics in the footers.
+/// Statistics that are missing from any row group are left out.
+pub fn footer_statistics(
+    file_path: &str,
+    schema: &Schema,
+) -> Result<HashMap<String, HashMap<String, serde_json::Value>>, String> {
+    let mut columns: Vec<FooterColumn> = schema
+        .iter()
+        .map(|(_, dtype)| FooterColumn::new(dtype))
+        .collect();
 
-    LazyFrame::scan_parquet(PlPath::from_str(file_path), Default::default())
-        .map_err(|e| format!("Failed to scan Parquet file: {}", e))
+    for file in dataset_files(file_path)? {
+        let metadata = read_parquet_metadata(&file)?;
+        let descr = metadata.file_metadata().schema_descr();
+
+        for ((name, dtype), column) in schema.iter().zip(columns.iter_mut()) {
+            // Nested columns have no top-level statistics, and hive partition
+            // columns are not stored in the files
+            let idx = (0..descr.num_columns()).find(|&i| {
+                let column = descr.column(i);
+                let parts = column.path().parts();
+                parts.len() == 1 && parts[0] == name.as_str()
+            });
+            let Some(idx) = idx else {
+                column.nulls = None;
+                column.bounded = false;
+                continue;
+            };
+
+            for row_group in metadata.row_groups() {
+                column.add(row_group.column(idx), dtype);
+            }
+        }
+    }
+
+    let mut stats = HashMap::new();
+    for ((name, _), column) in schema.iter().zip(columns) {
+        let mut column_stats = HashMap::new();
+        if let Some(nulls) = column.nulls {
+            column_stats.insert("null_values".to_string(), serde_json::json!(nulls));
+        }
+        if let (true, Some((min, max))) = (column.bounded, column.bounds) {
+            column_stats.insert("min".to_string(), serde_json::json!(min));
+            column_stats.insert("max".to_string(), serde_json::json!(max));
+        }
+        if !column_stats.is_empty() {
+            stats.insert(name.to_string(), column_stats);
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Footer statistics of a view of a file, so they are not computed again.
+/// Footers describe every row of a Parquet file, so filtered views and other
+/// formats have none. Statistics that can't be read are computed instead.
+pub fn view_footer_statistics(
+    file_path: &str,
+    lf: &LazyFrame,
+    filters: Option<&[Filter]>,
+) -> Result<HashMap<String, HashMap<String, serde_json::Value>>, String> {
+    let filtered = filters.is_some_and(|filters| !filters.is_empty());
+    if filtered || resolve_format(file_path)?.kind != FormatKind::Parquet {
+        return Ok(HashMap::new());
+    }
+
+    let schema = lf
+        .clone()
+        .collect_schema()
+        .map_err(|e| format!("Failed to read schema: {}", e))?;
+    Ok(footer_statistics(file_path, &schema).unwrap_or_default())
+}
+
+/// Footer statistics of one column, accumulated over row groups
+struct FooterColumn {
+    /// `None` once a row group has no null count
+    nulls: Option<usize>,
+    /// False once a row group with values has no min/max, or for columns that
+    /// are not numeric
+    bounded: bool,
+    bounds: Option<(f64, f64)>,
+}
+
+impl FooterColumn {
+    fn new(dtype: &DataType) -> Self {
+        Self {
+            nulls: Some(0),
+            bounded: dtype.is_primitive_numeric(),
+            bounds: None,
+        }
+    }
+
+    fn add(&mut self, chunk: &ColumnChunkMetaData, dtype: &DataType) {
+        let statistics = chunk.statistics();
+        let chunk_nulls = statistics.and_then(|s| s.null_count_opt());
+        self.nulls = self.nulls.zip(chunk_nulls).map(|(n, c)| n + c as usize);
+
+        // Row groups holding only nulls have no min/max
+        if !self.bounded || chunk_nulls == Some(chunk.num_values() as u64) {
+            return;
+        }
+
+        match statistics.and_then(|s| statistics_bounds(s, dtype)) {
+            Some((min, max)) => {
+                self.bounds = Some(match self.bounds {
+                    Some((lo, hi)) => (lo.min(min), hi.max(max)),
+                    None => (min, max),
+                });
+            }
+            None => self.bounded = false,
+        }
+    }
 }
 
-/// Check if the Parquet file schema contains unsupported types
-fn check_schema_compatibility(file_path: &str) -> Result<(), String> {
-    let file = File::open(file_path)
-        .map_err(|e| format!("Failed to open file: {}", e))?;
+/// Min and max of a column chunk. Unsigned columns are stored as signed
+/// integers of the same width.
+fn statistics_bounds(statistics: &Statistics, dtype: &DataType) -> Option<(f64, f64)> {
+    let unsigned = dtype.is_unsigned_integer();
+
+    match statistics {
+        Statistics::Int32(s) => {
+            let (min, max) = (*s.min_opt()?, *s.max_opt()?);
+            if unsigned {
+                Some((min as u32 as f64, max as u32 as f64))
+            } else {
+                Some((min as f64, max as f64))
+            }
+        }
+        Statistics::Int64(s) => {
+            let (min, max) = (*s.min_opt()?, *s.max_opt()?);
+            if unsigned {
+                Some((min as u64 as f64, max as u64 as f64))
+            } else {
+                Some((min as f64, max as f64))
+            }
+        }
+        Statistics::Float(s) => Some((*s.min_opt()? as f64, *s.max_opt()? as f64)),
+        Statistics::Double(s) => Some((*s.min_opt()?, *s.max_opt()?)),
+        _ => None,
+    }
+}
+
+/// Read the footer of a single Parquet file
+pub fn read_parquet_metadata(file_path: &Path) -> Result<ParquetMetaData, String> {
+    let file = File::open(file_path).map_err(|e| format!("Failed to open file: {}", e))?;
 
     let reader = SerializedFileReader::new(file)
         .map_err(|e| format!("Failed to read Parquet metadata: {}", e))?;
 
-    let schema = reader.metadata().file_metadata().schema_descr();
+    Ok(reader.metadata().clone())
+}
+
+/// The directory a glob pattern is rooted at, or the path itself otherwise
+pub fn dataset_root(file_path: &str) -> PathBuf {
+    if !is_glob(file_path) {
+        return PathBuf::from(file_path);
+    }
+
+    Path::new(file_path)
+        .components()
+        .take_while(|c| !matches!(c, Component::Normal(s) if has_glob_chars(&s.to_string_lossy())))
+        .collect()
+}
+
+/// Returns true for glob patterns. A file or directory that exists is never a
+/// glob, even if its name has glob characters, e.g. `export [1].parquet`.
+pub(super) fn is_glob(file_path: &str) -> bool {
+    has_glob_chars(file_path) && !Path::new(file_path).exists()
+}
+
+fn has_glob_chars(file_path: &str) -> bool {
+    file_path.contains(['*', '?', '['])
+}
+
+/// Dataset files are Parquet by extension, or by their magic bytes for
+/// writers that leave the extension off (e.g. Hive's `000000_0`)
+fn is_parquet_file(path: &Path) -> bool {
+    let by_extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("parquet"));
+    if by_extension {
+        return true;
+    }
 
-    // Check for FixedSizeList types
-    for field in schema.columns() {
-        let type_str = format!("{:?}", field.physical_type());
-        let converted_type_str = format!("{:?}", field.converted_type());
+    let mut magic = [0u8; 4];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok_and(|_| sniff_parquet(&magic))
+}
+
+fn is_hidden_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| n.starts_with('.') || n.starts_with('_'))
+        .unwrap_or(false)
+}
 
-        if converted_type_str.contains("LIST") && type_str.contains("Fixed") {
-            return Err(format!(
-                "Column '{}' contains Array/FixedSizeList type which is not fully supported. \
-                Please convert Array columns to List (variable-size) in your data generation script. \
-                Remove the .list.to_array() conversion or use regular lists instead of arrays.",
-                field.name()
-            ));
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read directory: {}", e))?;
+
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Failed to read directory: {}", e))?
+            .path();
+
+        if is_hidden_file(&path) {
+            continue;
+        }
+
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else if is_parquet_file(&path) {
+            files.push(path);
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_loader::dataframe_processor::dataframe_to_rows;
+    use crate::data_loader::test_utils::{path_str, temp_dir, write_parquet};
+
+    fn sample() -> DataFrame {
+        df!("a" => [1i64, 2, 3]).unwrap()
+    }
+
+    #[test]
+    fn footer_statistics_match_the_collected_values() {
+        let dir = temp_dir("footer-statistics");
+        let file = dir.join("a.parquet");
+        let mut df = df!(
+            "i" => [Some(3i64), None, Some(-7), Some(10), None, Some(4)],
+            "f" => [Some(1.5f64), Some(-2.0), None, Some(8.25), Some(0.0), Some(3.0)],
+        )
+        .unwrap();
+        write_parquet(&file, &mut df, 2);
+        let lf = open_parquet(path_str(&file)).unwrap();
+
+        let footer = view_footer_statistics(path_str(&file), &lf, None).unwrap();
+        let collected =
+            crate::data_loader::calculate_statistics_lazy(&lf, None, HashMap::new(), |lf| {
+                lf.collect().map_err(|e| e.to_string())
+            })
+            .unwrap();
+        for column in ["i", "f"] {
+            for key in ["min", "max", "null_values"] {
+                assert_eq!(
+                    footer[column][key], collected[column][key],
+                    "{} of {}",
+                    key, column
+                );
+            }
+        }
+
+        // Footers describe every row, not the rows a filter keeps
+        let filters: Vec<Filter> = serde_json::from_value(serde_json::json!([
+            {"kind": "compare", "column": "i", "op": "gt", "value": 0}
+        ]))
+        .unwrap();
+        let filtered = view_footer_statistics(path_str(&file), &lf, Some(&filters)).unwrap();
+        assert!(filtered.is_empty());
+        let unfiltered = view_footer_statistics(path_str(&file), &lf, Some(&[])).unwrap();
+        assert_eq!(unfiltered.len(), 2);
+    }
+
+    #[test]
+    fn fixed_size_list_columns_are_loaded_and_formatted() {
+        let dir = temp_dir("fixed-size-list");
+        let path = dir.join("embeddings.parquet");
+        let vector = |values: &[Option<i64>]| Some(Series::new(PlSmallStr::EMPTY, values));
+        let lists: ListChunked = [
+            vector(&[Some(1), Some(2), Some(3)]),
+            None,
+            vector(&[Some(-4), None, Some(6)]),
+        ]
+        .into_iter()
+        .collect();
+        let embedding = lists
+            .into_series()
+            .with_name("embedding".into())
+            .cast(&DataType::Array(Box::new(DataType::Int64), 3))
+            .unwrap();
+        let mut df = DataFrame::new(vec![embedding.into()]).unwrap();
+        write_parquet(&path, &mut df, 10);
+
+        // Files with fixed-size lists used to be rejected before scanning
+        let mut lf = open_parquet(path_str(&path)).unwrap();
+        let schema = lf.collect_schema().unwrap();
+        assert_eq!(
+            schema.get("embedding"),
+            Some(&DataType::Array(Box::new(DataType::Int64), 3))
+        );
+
+        let rows = dataframe_to_rows(&lf.collect().unwrap()).unwrap();
+        assert_eq!(rows, [vec!["[1, 2, 3]"], vec![""], vec!["[-4, null, 6]"]]);
+    }
+
+    #[test]
+    fn existing_file_with_glob_characters_is_not_a_glob() {
+        let dir = temp_dir("glob-chars");
+        let file = dir.join("data?[1].parquet");
+        write_parquet(&file, &mut sample(), 10);
+
+        assert!(!is_glob(path_str(&file)));
+        assert!(is_glob(path_str(&dir.join("*.parquet"))));
+        assert_eq!(dataset_root(path_str(&file)), file);
+        assert_eq!(dataset_root(path_str(&dir.join("*.parquet"))), dir);
+
+        let df = open_parquet(path_str(&file)).unwrap().collect().unwrap();
+        assert_eq!(df.height(), 3);
+    }
+
+    #[test]
+    fn dataset_files_skip_files_that_are_not_parquet() {
+        let dir = temp_dir("dataset-files");
+        write_parquet(&dir.join("part-0.parquet"), &mut sample(), 10);
+        // Hive writes data files without an extension
+        write_parquet(&dir.join("000000_0"), &mut sample(), 10);
+        fs::write(dir.join("_SUCCESS"), "").unwrap();
+        fs::write(dir.join(".part-0.parquet.crc"), "crc").unwrap();
+        fs::write(dir.join("README"), "not data").unwrap();
+
+        let files = dataset_files(path_str(&dir)).unwrap();
+        assert_eq!(
+            files,
+            vec![dir.join("000000_0"), dir.join("part-0.parquet")]
+        );
+
+        let globbed = dataset_files(path_str(&dir.join("*"))).unwrap();
+        assert_eq!(globbed, files);
+
+        let df = open_parquet(path_str(&dir)).unwrap().collect().unwrap();
+        assert_eq!(df.height(), 6);
+    }
+
+    #[test]
+    fn hive_partitions_become_columns() {
+        let dir = temp_dir("hive");
+        for year in ["2023", "2024"] {
+            let partition = dir.join(format!("year={}", year));
+            fs::create_dir_all(&partition).unwrap();
+            write_parquet(&partition.join("part-0.parquet"), &mut sample(), 10);
+        }
+        fs::write(dir.join("_SUCCESS"), "").unwrap();
+
+        let df = open_parquet(path_str(&dir)).unwrap().collect().unwrap();
+        assert_eq!(df.height(), 6);
+        assert!(df.column("year").is_ok());
+    }
+
+    #[test]
+    fn empty_dataset_is_an_error() {
+        let dir = temp_dir("empty-dataset");
+        fs::write(dir.join("README"), "not data").unwrap();
+        assert!(dataset_files(path_str(&dir)).is_err());
+    }
+}
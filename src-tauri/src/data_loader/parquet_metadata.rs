@@ -0,0 +1,601 @@
+use parquet::arrow::parquet_to_arrow_schema;
+use parquet::basic::Type as PhysicalType;
+use parquet::data_type::Int96;
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData, RowGroupMetaData};
+use parquet::file::reader::FileReader;
+use parquet::file::serialized_reader::{ReadOptionsBuilder, SerializedFileReader};
+use parquet::file::statistics::Statistics;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufReader, Seek, SeekFrom};
+
+use super::page_header::{encoding_name, page_type_name, read_page_header, PageStatistics};
+use super::parquet_loader::{dataset_files, read_parquet_metadata};
+
+/// Key of the Arrow schema that Arrow-based writers embed in the footer
+const ARROW_SCHEMA_KEY: &str = "ARROW:schema";
+
+/// Key of the pandas metadata that pandas embeds in the footer
+const PANDAS_KEY: &str = "pandas";
+
+/// Footer of one Parquet file
+#[derive(Serialize)]
+pub struct ParquetFooter {
+    pub path: String,
+    pub created_by: Option<String>,
+    pub version: i32,
+    pub num_rows: i64,
+    pub key_value_metadata: Vec<KeyValueInfo>,
+    /// Fields of the embedded Arrow schema
+    pub arrow_schema: Option<Vec<ArrowFieldInfo>>,
+    /// Embedded pandas metadata, parsed from JSON
+    pub pandas_metadata: Option<serde_json::Value>,
+    pub row_groups: Vec<RowGroupInfo>,
+}
+
+#[derive(Serialize)]
+pub struct KeyValueInfo {
+    pub key: String,
+    pub value: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ArrowFieldInfo {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+}
+
+#[derive(Serialize)]
+pub struct RowGroupInfo {
+    pub index: usize,
+    pub num_rows: i64,
+    /// Uncompressed size of the column data
+    pub total_byte_size: i64,
+    pub compressed_size: i64,
+    pub file_offset: Option<i64>,
+    /// Columns the rows are sorted by, as written by the writer
+    pub sorting_columns: Vec<SortingColumnInfo>,
+    pub columns: Vec<ColumnChunkInfo>,
+}
+
+#[derive(Serialize)]
+pub struct SortingColumnInfo {
+    pub column: String,
+    pub descending: bool,
+    pub nulls_first: bool,
+}
+
+#[derive(Serialize)]
+pub struct ColumnChunkInfo {
+    /// Dot-separated path of the leaf column
+    pub path: String,
+    pub physical_type: String,
+    pub compression: String,
+    pub encodings: Vec<String>,
+    pub num_values: i64,
+    pub compressed_size: i64,
+    pub uncompressed_size: i64,
+    pub statistics: Option<StatisticsInfo>,
+    pub data_page_offset: i64,
+    pub dictionary_page_offset: Option<i64>,
+    pub index_page_offset: Option<i64>,
+    pub column_index_offset: Option<i64>,
+    pub column_index_length: Option<i32>,
+    pub offset_index_offset: Option<i64>,
+    pub offset_index_length: Option<i32>,
+    pub bloom_filter_offset: Option<i64>,
+    pub bloom_filter_length: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct StatisticsInfo {
+    pub min: Option<String>,
+    pub max: Option<String>,
+    pub null_count: Option<u64>,
+    pub distinct_count: Option<u64>,
+    /// False if the writer truncated the value, e.g. long strings
+    pub min_is_exact: bool,
+    pub max_is_exact: bool,
+}
+
+/// Footers of every file of a Parquet path
+pub fn parquet_footers(file_path: &str) -> Result<Vec<ParquetFooter>, String> {
+    dataset_files(file_path)?
+        .iter()
+        .map(|file| {
+            let metadata = read_parquet_metadata(file)?;
+            Ok(parquet_footer(&file.to_string_lossy(), &metadata))
+        })
+        .collect()
+}
+
+fn parquet_footer(path: &str, metadata: &ParquetMetaData) -> ParquetFooter {
+    let file_metadata = metadata.file_metadata();
+    let key_value = file_metadata.key_value_metadata();
+    let value_of = |key: &str| {
+        key_value?
+            .iter()
+            .find(|kv| kv.key == key)
+            .and_then(|kv| kv.value.as_deref())
+    };
+
+    let arrow_schema = value_of(ARROW_SCHEMA_KEY)
+        .and_then(|_| parquet_to_arrow_schema(file_metadata.schema_descr(), key_value).ok())
+        .map(|schema| {
+            schema
+                .fields()
+                .iter()
+                .map(|field| ArrowFieldInfo {
+                    name: field.name().clone(),
+                    data_type: field.data_type().to_string(),
+                    nullable: field.is_nullable(),
+                })
+                .collect()
+        });
+
+    ParquetFooter {
+        path: path.to_string(),
+        created_by: file_metadata.created_by().map(|s| s.to_string()),
+        version: file_metadata.version(),
+        num_rows: file_metadata.num_rows(),
+        key_value_metadata: key_value
+            .map(|kvs| {
+                kvs.iter()
+                    .map(|kv| KeyValueInfo {
+                        key: kv.key.clone(),
+                        value: kv.value.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        arrow_schema,
+        pandas_metadata: value_of(PANDAS_KEY).and_then(|value| serde_json::from_str(value).ok()),
+        row_groups: metadata
+            .row_groups()
+            .iter()
+            .enumerate()
+            .map(|(index, row_group)| row_group_info(index, row_group))
+            .collect(),
+    }
+}
+
+fn row_group_info(index: usize, row_group: &RowGroupMetaData) -> RowGroupInfo {
+    let sorting_columns = row_group
+        .sorting_columns()
+        .map(|sorting| {
+            sorting
+                .iter()
+                .map(|column| SortingColumnInfo {
+                    column: row_group
+                        .schema_descr()
+                        .column(column.column_idx as usize)
+                        .path()
+                        .string(),
+                    descending: column.descending,
+                    nulls_first: column.nulls_first,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    RowGroupInfo {
+        index,
+        num_rows: row_group.num_rows(),
+        total_byte_size: row_group.total_byte_size(),
+        compressed_size: row_group.compressed_size(),
+        file_offset: row_group.file_offset(),
+        sorting_columns,
+        columns: row_group.columns().iter().map(column_chunk_info).collect(),
+    }
+}
+
+fn column_chunk_info(column: &ColumnChunkMetaData) -> ColumnChunkInfo {
+    ColumnChunkInfo {
+        path: column.column_path().string(),
+        physical_type: column.column_type().to_string(),
+        compression: column.compression().to_string(),
+        encodings: column.encodings().map(|e| e.to_string()).collect(),
+        num_values: column.num_values(),
+        compressed_size: column.compressed_size(),
+        uncompressed_size: column.uncompressed_size(),
+        statistics: column.statistics().map(statistics_info),
+        data_page_offset: column.data_page_offset(),
+        dictionary_page_offset: column.dictionary_page_offset(),
+        index_page_offset: column.index_page_offset(),
+        column_index_offset: column.column_index_offset(),
+        column_index_length: column.column_index_length(),
+        offset_index_offset: column.offset_index_offset(),
+        offset_index_length: column.offset_index_length(),
+        bloom_filter_offset: column.bloom_filter_offset(),
+        bloom_filter_length: column.bloom_filter_length(),
+    }
+}
+
+pub(super) fn statistics_info(statistics: &Statistics) -> StatisticsInfo {
+    let (min, max) = statistics_bounds_text(statistics);
+
+    StatisticsInfo {
+        min,
+        max,
+        null_count: statistics.null_count_opt(),
+        distinct_count: statistics.distinct_count_opt(),
+        min_is_exact: statistics.min_is_exact(),
+        max_is_exact: statistics.max_is_exact(),
+    }
+}
+
+/// Min and max of a column chunk as text. Byte arrays that are not UTF-8 are
+/// shown as hex.
+fn statistics_bounds_text(statistics: &Statistics) -> (Option<String>, Option<String>) {
+    match statistics {
+        Statistics::Boolean(s) => (
+            s.min_opt().map(|v| v.to_string()),
+            s.max_opt().map(|v| v.to_string()),
+        ),
+        Statistics::Int32(s) => (
+            s.min_opt().map(|v| v.to_string()),
+            s.max_opt().map(|v| v.to_string()),
+        ),
+        Statistics::Int64(s) => (
+            s.min_opt().map(|v| v.to_string()),
+            s.max_opt().map(|v| v.to_string()),
+        ),
+        Statistics::Int96(s) => (
+            s.min_opt().map(|v| v.to_string()),
+            s.max_opt().map(|v| v.to_string()),
+        ),
+        Statistics::Float(s) => (
+            s.min_opt().map(|v| v.to_string()),
+            s.max_opt().map(|v| v.to_string()),
+        ),
+        Statistics::Double(s) => (
+            s.min_opt().map(|v| v.to_string()),
+            s.max_opt().map(|v| v.to_string()),
+        ),
+        Statistics::ByteArray(s) => (
+            s.min_opt().map(|v| bytes_text(v.data())),
+            s.max_opt().map(|v| bytes_text(v.data())),
+        ),
+        Statistics::FixedLenByteArray(s) => (
+            s.min_opt().map(|v| bytes_text(v.data())),
+            s.max_opt().map(|v| bytes_text(v.data())),
+        ),
+    }
+}
+
+/// Statistics of a page header, whose min and max are in the plain encoding
+/// of the column's physical type
+fn page_statistics_info(physical_type: PhysicalType, statistics: PageStatistics) -> StatisticsInfo {
+    let text = |bytes: Option<Vec<u8>>| bytes.map(|bytes| plain_value_text(physical_type, &bytes));
+
+    StatisticsInfo {
+        min: text(statistics.min),
+        max: text(statistics.max),
+        null_count: statistics.null_count.and_then(|n| u64::try_from(n).ok()),
+        distinct_count: statistics
+            .distinct_count
+            .and_then(|n| u64::try_from(n).ok()),
+        min_is_exact: statistics.min_is_exact.unwrap_or(false),
+        max_is_exact: statistics.max_is_exact.unwrap_or(false),
+    }
+}
+
+/// A plain encoded value as text, formatted like the chunk statistics.
+/// Values too short for their type are shown as hex.
+fn plain_value_text(physical_type: PhysicalType, bytes: &[u8]) -> String {
+    match physical_type {
+        PhysicalType::BOOLEAN if bytes.len() == 1 => (bytes[0] != 0).to_string(),
+        PhysicalType::INT32 => match bytes.try_into() {
+            Ok(bytes) => i32::from_le_bytes(bytes).to_string(),
+            Err(_) => hex_text(bytes),
+        },
+        PhysicalType::INT64 => match bytes.try_into() {
+            Ok(bytes) => i64::from_le_bytes(bytes).to_string(),
+            Err(_) => hex_text(bytes),
+        },
+        PhysicalType::FLOAT => match bytes.try_into() {
+            Ok(bytes) => f32::from_le_bytes(bytes).to_string(),
+            Err(_) => hex_text(bytes),
+        },
+        PhysicalType::DOUBLE => match bytes.try_into() {
+            Ok(bytes) => f64::from_le_bytes(bytes).to_string(),
+            Err(_) => hex_text(bytes),
+        },
+        PhysicalType::INT96 if bytes.len() == 12 => {
+            let word = |i: usize| u32::from_le_bytes(bytes[i * 4..i * 4 + 4].try_into().unwrap());
+            let mut value = Int96::new();
+            value.set_data(word(0), word(1), word(2));
+            value.to_string()
+        }
+        PhysicalType::BYTE_ARRAY | PhysicalType::FIXED_LEN_BYTE_ARRAY => bytes_text(bytes),
+        _ => hex_text(bytes),
+    }
+}
+
+/// Byte arrays as text, or as hex when they are not UTF-8
+fn bytes_text(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => hex_text(bytes),
+    }
+}
+
+fn hex_text(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Layout of the pages of one column chunk
+#[derive(Serialize)]
+pub struct ColumnPages {
+    pub path: String,
+    pub row_group: usize,
+    pub compression: String,
+    pub has_dictionary_page: bool,
+    pub data_page_count: usize,
+    pub has_column_index: bool,
+    pub has_offset_index: bool,
+    pub has_bloom_filter: bool,
+    /// Number of pages per page type and encoding, as recorded by the writer
+    pub encoding_stats: Vec<PageEncodingInfo>,
+    pub pages: Vec<PageInfo>,
+}
+
+#[derive(Serialize)]
+pub struct PageEncodingInfo {
+    pub page_type: String,
+    pub encoding: String,
+    pub count: i32,
+}
+
+#[derive(Serialize)]
+pub struct PageInfo {
+    pub page_type: String,
+    /// Not recorded for index pages
+    pub encoding: Option<String>,
+    pub num_values: u32,
+    /// Offset of the page header in the file
+    pub offset: u64,
+    pub header_size: usize,
+    /// Size of the page data in the file, without the page header
+    pub compressed_size: usize,
+    /// Size of the page data after decompression, without the page header
+    pub uncompressed_size: usize,
+    /// From the offset index, for data pages of files with a page index
+    pub first_row_index: Option<i64>,
+    /// Only recorded in v2 data pages
+    pub num_rows: Option<u32>,
+    pub num_nulls: Option<u32>,
+    pub statistics: Option<StatisticsInfo>,
+}
+
+/// Walk the pages of a column chunk of a Parquet file. Only the page headers
+/// are read; the page data is skipped without being decompressed.
+pub fn column_pages(
+    file_path: &str,
+    row_group: usize,
+    column: &str,
+) -> Result<ColumnPages, String> {
+    let file = File::open(file_path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let options = ReadOptionsBuilder::new().with_page_index().build();
+    let reader = SerializedFileReader::new_with_options(file, options)
+        .map_err(|e| format!("Failed to read Parquet metadata: {}", e))?;
+    let metadata = reader.metadata();
+
+    if row_group >= metadata.num_row_groups() {
+        return Err(format!(
+            "Row group {} not found, the file has {}",
+            row_group,
+            metadata.num_row_groups()
+        ));
+    }
+    let row_group_metadata = metadata.row_group(row_group);
+    let column_idx = row_group_metadata
+        .columns()
+        .iter()
+        .position(|chunk| chunk.column_path().string() == column)
+        .ok_or_else(|| format!("Column not found: {}", column))?;
+    let chunk = row_group_metadata.column(column_idx);
+    let physical_type = chunk.column_type();
+
+    // Data page locations, to find the first row of each data page
+    let locations = metadata
+        .offset_index()
+        .and_then(|index| index.get(row_group)?.get(column_idx))
+        .map(|index| index.page_locations().clone())
+        .unwrap_or_default();
+
+    let (start, length) = chunk.byte_range();
+    let end = start + length;
+    let mut file =
+        BufReader::new(File::open(file_path).map_err(|e| format!("Failed to open file: {}", e))?);
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| format!("Failed to read column chunk: {}", e))?;
+
+    let mut pages = Vec::new();
+    let mut data_page_count = 0;
+    let mut offset = start;
+    while offset < end {
+        let (header, header_size) = read_page_header(&mut file)
+            .map_err(|e| format!("Failed to read page header at offset {}: {}", offset, e))?;
+        let compressed_size = usize::try_from(header.compressed_size)
+            .map_err(|_| format!("Invalid page size at offset {}", offset))?;
+
+        let page_type = page_type_name(header.page_type);
+        if page_type.starts_with("DATA_PAGE") {
+            data_page_count += 1;
+        }
+
+        pages.push(PageInfo {
+            page_type,
+            encoding: header.encoding.map(encoding_name),
+            num_values: header.num_values.unwrap_or(0) as u32,
+            offset,
+            header_size,
+            compressed_size,
+            uncompressed_size: header.uncompressed_size.max(0) as usize,
+            first_row_index: locations
+                .iter()
+                .find(|location| location.offset as u64 == offset)
+                .map(|location| location.first_row_index),
+            num_rows: header.num_rows.map(|n| n as u32),
+            num_nulls: header.num_nulls.map(|n| n as u32),
+            statistics: header
+                .statistics
+                .map(|statistics| page_statistics_info(physical_type, statistics)),
+        });
+
+        file.seek_relative(compressed_size as i64)
+            .map_err(|e| format!("Failed to read column chunk: {}", e))?;
+        offset += (header_size + compressed_size) as u64;
+    }
+
+    Ok(ColumnPages {
+        path: column.to_string(),
+        row_group,
+        compression: chunk.compression().to_string(),
+        // Some writers leave the dictionary page offset out of the footer
+        has_dictionary_page: pages.iter().any(|page| page.page_type == "DICTIONARY_PAGE"),
+        data_page_count,
+        has_column_index: chunk.column_index_offset().is_some(),
+        has_offset_index: chunk.offset_index_offset().is_some(),
+        has_bloom_filter: chunk.bloom_filter_offset().is_some(),
+        encoding_stats: chunk
+            .page_encoding_stats()
+            .map(|stats| {
+                stats
+                    .iter()
+                    .map(|s| PageEncodingInfo {
+                        page_type: s.page_type.to_string(),
+                        encoding: s.encoding.to_string(),
+                        count: s.count,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        pages,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_loader::test_utils::{path_str, temp_dir, write_parquet};
+    use polars::prelude::*;
+
+    #[test]
+    fn footers_describe_each_row_group_and_column_chunk() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+        use parquet::arrow::ArrowWriter;
+        use parquet::basic::Compression;
+        use parquet::file::properties::WriterProperties;
+        use parquet::schema::types::ColumnPath;
+
+        let dir = temp_dir("parquet-footers");
+        let source = dir.join("source.parquet");
+        let file = dir.join("a.parquet");
+        let mut df = df!(
+            "id" => [1i64, 2, 3, 4],
+            "label" => ["a", "b", "a", "b"]
+        )
+        .unwrap();
+        write_parquet(&source, &mut df, 4);
+
+        // Rewrite the file with a different codec and encoding per column
+        let batches = ParquetRecordBatchReaderBuilder::try_new(File::open(&source).unwrap())
+            .unwrap()
+            .build()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .set_column_compression(ColumnPath::from("id"), Compression::SNAPPY)
+            .set_column_compression(ColumnPath::from("label"), Compression::UNCOMPRESSED)
+            .set_column_dictionary_enabled(ColumnPath::from("id"), false)
+            .build();
+        let mut writer = ArrowWriter::try_new(
+            File::create(&file).unwrap(),
+            batches[0].schema(),
+            Some(properties),
+        )
+        .unwrap();
+        for batch in &batches {
+            writer.write(batch).unwrap();
+        }
+        writer.close().unwrap();
+
+        let footers = parquet_footers(path_str(&file)).unwrap();
+        assert_eq!(footers.len(), 1);
+        let footer = &footers[0];
+        assert!(footer
+            .created_by
+            .as_deref()
+            .unwrap()
+            .starts_with("parquet-rs"));
+        assert_eq!(footer.num_rows, 4);
+
+        let arrow_schema = footer.arrow_schema.as_ref().unwrap();
+        let fields: Vec<_> = arrow_schema
+            .iter()
+            .map(|field| (field.name.as_str(), field.data_type.as_str()))
+            .collect();
+        assert_eq!(fields, [("id", "Int64"), ("label", "LargeUtf8")]);
+
+        assert_eq!(footer.row_groups.len(), 2);
+        for row_group in &footer.row_groups {
+            assert_eq!(row_group.num_rows, 2);
+            let id = &row_group.columns[0];
+            let label = &row_group.columns[1];
+            assert_eq!((id.path.as_str(), label.path.as_str()), ("id", "label"));
+            assert_eq!(id.compression, "SNAPPY");
+            assert_eq!(label.compression, "UNCOMPRESSED");
+            assert!(!id.encodings.contains(&"RLE_DICTIONARY".to_string()));
+            assert!(label.encodings.contains(&"RLE_DICTIONARY".to_string()));
+            assert!(id.dictionary_page_offset.is_none());
+        }
+        let statistics = footer.row_groups[1].columns[0].statistics.as_ref().unwrap();
+        assert_eq!(statistics.min.as_deref(), Some("3"));
+        assert_eq!(statistics.max.as_deref(), Some("4"));
+    }
+
+    #[test]
+    fn column_pages_cover_the_column_chunk() {
+        let dir = temp_dir("column-pages");
+        let file = dir.join("a.parquet");
+        let mut df = df!(
+            "id" => (0..5000i64).collect::<Vec<_>>(),
+            "label" => (0..5000).map(|i| format!("label-{}", i % 7)).collect::<Vec<_>>()
+        )
+        .unwrap();
+        write_parquet(&file, &mut df, 5000);
+
+        let reader = SerializedFileReader::new(File::open(&file).unwrap()).unwrap();
+        for (idx, column) in ["id", "label"].iter().enumerate() {
+            let pages = column_pages(path_str(&file), 0, column).unwrap();
+            let chunk = reader.metadata().row_group(0).column(idx);
+            let (start, length) = chunk.byte_range();
+
+            // Pages follow each other from the start to the end of the chunk
+            assert_eq!(pages.pages[0].offset, start);
+            let mut offset = start;
+            for page in &pages.pages {
+                assert_eq!(page.offset, offset);
+                offset += (page.header_size + page.compressed_size) as u64;
+            }
+            assert_eq!(offset, start + length);
+
+            let data_values: u32 = pages
+                .pages
+                .iter()
+                .filter(|page| page.page_type.starts_with("DATA_PAGE"))
+                .map(|page| page.num_values)
+                .sum();
+            assert_eq!(data_values, 5000);
+            assert_eq!(
+                pages.data_page_count,
+                pages.pages.len() - pages.has_dictionary_page as usize
+            );
+        }
+    }
+}
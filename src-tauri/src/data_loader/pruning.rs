@@ -0,0 +1,445 @@
+use parquet::file::metadata::RowGroupMetaData;
+use parquet::file::statistics::Statistics;
+use polars::prelude::*;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use super::dataframe_processor::{check_equality, column_dtype, filter_literal, CompareOp, Filter};
+use super::parquet_loader::{dataset_files, read_parquet_metadata};
+
+/// Row groups a filter would skip based on the min/max and null count
+/// statistics in the Parquet footers
+#[derive(Serialize)]
+pub struct PruningReport {
+    pub row_groups: usize,
+    pub row_groups_skipped: usize,
+    pub rows_skipped: usize,
+    pub rows_read: usize,
+    /// Compressed size of the row groups
+    pub bytes_skipped: i64,
+    pub bytes_read: i64,
+    pub details: Vec<RowGroupPruning>,
+}
+
+#[derive(Serialize)]
+pub struct RowGroupPruning {
+    /// Row group number across the files of a dataset
+    pub row_group: usize,
+    pub path: String,
+    pub index: usize,
+    pub num_rows: usize,
+    pub compressed_size: i64,
+    pub skipped: bool,
+}
+
+/// A filter with its values converted for comparison with statistics
+enum Condition {
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+    Compare {
+        column: String,
+        op: CompareOp,
+        value: Option<Bound>,
+    },
+    Between {
+        column: String,
+        low: Option<Bound>,
+        high: Option<Bound>,
+    },
+    In {
+        column: String,
+        values: Vec<Option<Bound>>,
+    },
+    IsNull {
+        column: String,
+    },
+    IsNotNull {
+        column: String,
+    },
+    /// String matching, which only all-null row groups rule out
+    Matches {
+        column: String,
+    },
+}
+
+/// Value in the physical representation used by Parquet statistics: days for
+/// dates and the time unit of the column for timestamps
+#[derive(Debug, PartialEq)]
+enum Bound {
+    Integer(i128),
+    Float(f64),
+    Text(String),
+}
+
+impl Bound {
+    fn compare(&self, other: &Bound) -> Option<Ordering> {
+        match (self, other) {
+            (Bound::Integer(a), Bound::Integer(b)) => Some(a.cmp(b)),
+            (Bound::Float(a), Bound::Float(b)) => a.partial_cmp(b),
+            (Bound::Integer(a), Bound::Float(b)) => (*a as f64).partial_cmp(b),
+            (Bound::Float(a), Bound::Integer(b)) => a.partial_cmp(&(*b as f64)),
+            (Bound::Text(a), Bound::Text(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+}
+
+/// Statistics of a column chunk. Missing statistics leave the chunk unbounded.
+struct ColumnBounds {
+    min: Option<Bound>,
+    max: Option<Bound>,
+    null_count: Option<u64>,
+    num_rows: u64,
+}
+
+impl ColumnBounds {
+    fn all_null(&self) -> bool {
+        self.null_count == Some(self.num_rows)
+    }
+
+    /// Whether every value is below `value` (or equal to it, if `or_equal`)
+    fn all_below(&self, value: &Option<Bound>, or_equal: bool) -> bool {
+        let ordering = self
+            .max
+            .as_ref()
+            .zip(value.as_ref())
+            .and_then(|(m, v)| m.compare(v));
+        matches!(ordering, Some(Ordering::Less)) || (or_equal && ordering == Some(Ordering::Equal))
+    }
+
+    /// Whether every value is above `value` (or equal to it, if `or_equal`)
+    fn all_above(&self, value: &Option<Bound>, or_equal: bool) -> bool {
+        let ordering = self
+            .min
+            .as_ref()
+            .zip(value.as_ref())
+            .and_then(|(m, v)| m.compare(v));
+        matches!(ordering, Some(Ordering::Greater))
+            || (or_equal && ordering == Some(Ordering::Equal))
+    }
+
+    fn outside(&self, value: &Option<Bound>) -> bool {
+        self.all_below(value, false) || self.all_above(value, false)
+    }
+}
+
+/// Check filters against the row group statistics of a Parquet path. A row
+/// group is skipped if its statistics show that no row can match.
+pub fn row_group_pruning(
+    file_path: &str,
+    schema: &Schema,
+    filters: &[Filter],
+) -> Result<PruningReport, String> {
+    let condition = Condition::All(
+        filters
+            .iter()
+            .map(|filter| condition(filter, schema))
+            .collect::<Result<_, _>>()?,
+    );
+
+    let mut details = Vec::new();
+    for file in dataset_files(file_path)? {
+        let metadata = read_parquet_metadata(&file)?;
+        for (index, row_group) in metadata.row_groups().iter().enumerate() {
+            let columns = row_group_bounds(row_group, schema);
+            details.push(RowGroupPruning {
+                row_group: details.len(),
+                path: file.to_string_lossy().to_string(),
+                index,
+                num_rows: row_group.num_rows() as usize,
+                compressed_size: row_group.compressed_size(),
+                skipped: can_skip(&condition, &columns),
+            });
+        }
+    }
+
+    let (skipped, read): (Vec<&RowGroupPruning>, Vec<&RowGroupPruning>) =
+        details.iter().partition(|group| group.skipped);
+
+    Ok(PruningReport {
+        row_groups: details.len(),
+        row_groups_skipped: skipped.len(),
+        rows_skipped: skipped.iter().map(|group| group.num_rows).sum(),
+        rows_read: read.iter().map(|group| group.num_rows).sum(),
+        bytes_skipped: skipped.iter().map(|group| group.compressed_size).sum(),
+        bytes_read: read.iter().map(|group| group.compressed_size).sum(),
+        details,
+    })
+}
+
+fn condition(filter: &Filter, schema: &Schema) -> Result<Condition, String> {
+    let bound = |column: &str, value: &serde_json::Value| -> Result<Option<Bound>, String> {
+        literal_bound(filter_literal(value, column_dtype(schema, column)?)?)
+    };
+
+    let condition = match filter {
+        Filter::And { filters } => Condition::All(
+            filters
+                .iter()
+                .map(|filter| condition(filter, schema))
+                .collect::<Result<_, _>>()?,
+        ),
+        Filter::Or { filters } => Condition::Any(
+            filters
+                .iter()
+                .map(|filter| condition(filter, schema))
+                .collect::<Result<_, _>>()?,
+        ),
+        Filter::Compare { column, op, value } => {
+            if matches!(op, CompareOp::Eq | CompareOp::NotEq) {
+                check_equality(column, value, column_dtype(schema, column)?)?;
+            }
+            Condition::Compare {
+                column: column.clone(),
+                op: *op,
+                value: bound(column, value)?,
+            }
+        }
+        Filter::Between { column, low, high } => Condition::Between {
+            column: column.clone(),
+            low: bound(column, low)?,
+            high: bound(column, high)?,
+        },
+        Filter::In { column, values } => Condition::In {
+            column: column.clone(),
+            values: values
+                .iter()
+                .map(|value| {
+                    check_equality(column, value, column_dtype(schema, column)?)?;
+                    bound(column, value)
+                })
+                .collect::<Result<_, _>>()?,
+        },
+        Filter::IsNull { column } => {
+            column_dtype(schema, column)?;
+            Condition::IsNull {
+                column: column.clone(),
+            }
+        }
+        Filter::IsNotNull { column } => {
+            column_dtype(schema, column)?;
+            Condition::IsNotNull {
+                column: column.clone(),
+            }
+        }
+        Filter::Contains { column, .. } | Filter::Regex { column, .. } => {
+            column_dtype(schema, column)?;
+            Condition::Matches {
+                column: column.clone(),
+            }
+        }
+    };
+
+    Ok(condition)
+}
+
+/// Evaluate a filter literal, cast to the type of its column, to the physical
+/// value statistics are compared with
+fn literal_bound(expr: Expr) -> Result<Option<Bound>, String> {
+    let df = DataFrame::empty()
+        .lazy()
+        .select([expr.alias("value")])
+        .collect()
+        .map_err(|e| format!("Invalid filter value: {}", e))?;
+    let column = df
+        .column("value")
+        .map_err(|e| format!("Invalid filter value: {}", e))?
+        .to_physical_repr();
+    let value = column
+        .get(0)
+        .map_err(|e| format!("Invalid filter value: {}", e))?;
+
+    Ok(match value {
+        AnyValue::Boolean(b) => Some(Bound::Integer(b as i128)),
+        AnyValue::String(s) => Some(Bound::Text(s.to_string())),
+        AnyValue::StringOwned(s) => Some(Bound::Text(s.to_string())),
+        AnyValue::Float32(f) if !f.is_nan() => Some(Bound::Float(f as f64)),
+        AnyValue::Float64(f) if !f.is_nan() => Some(Bound::Float(f)),
+        value => value.extract::<i128>().map(Bound::Integer),
+    })
+}
+
+/// Statistics of the top-level flat columns of a row group. Nested columns and
+/// hive partition columns are left out, so filters on them never skip.
+fn row_group_bounds(
+    row_group: &RowGroupMetaData,
+    schema: &Schema,
+) -> HashMap<String, ColumnBounds> {
+    let mut columns = HashMap::new();
+
+    for chunk in row_group.columns() {
+        let parts = chunk.column_path().parts();
+        if parts.len() != 1 {
+            continue;
+        }
+        let Some(dtype) = schema.get(parts[0].as_str()) else {
+            continue;
+        };
+
+        let statistics = chunk.statistics();
+        let (min, max) = statistics
+            .map(|s| statistics_range(s, dtype))
+            .unwrap_or((None, None));
+        columns.insert(
+            parts[0].clone(),
+            ColumnBounds {
+                min,
+                max,
+                null_count: statistics.and_then(|s| s.null_count_opt()),
+                num_rows: row_group.num_rows() as u64,
+            },
+        );
+    }
+
+    columns
+}
+
+/// Min and max of a column chunk in the representation of filter literals.
+/// Floats have no upper bound, as NaN is not recorded in the statistics but
+/// compares greater than any number.
+fn statistics_range(statistics: &Statistics, dtype: &DataType) -> (Option<Bound>, Option<Bound>) {
+    let comparable = dtype.is_primitive_numeric()
+        || matches!(
+            dtype,
+            DataType::Boolean | DataType::String | DataType::Date | DataType::Datetime(_, _)
+        );
+    if !comparable {
+        return (None, None);
+    }
+
+    let unsigned = dtype.is_unsigned_integer();
+    match statistics {
+        Statistics::Boolean(s) => (
+            s.min_opt().map(|v| Bound::Integer(*v as i128)),
+            s.max_opt().map(|v| Bound::Integer(*v as i128)),
+        ),
+        Statistics::Int32(s) => {
+            let bound = |v: &i32| match unsigned {
+                true => Bound::Integer(*v as u32 as i128),
+                false => Bound::Integer(*v as i128),
+            };
+            (s.min_opt().map(bound), s.max_opt().map(bound))
+        }
+        Statistics::Int64(s) => {
+            let bound = |v: &i64| match unsigned {
+                true => Bound::Integer(*v as u64 as i128),
+                false => Bound::Integer(*v as i128),
+            };
+            (s.min_opt().map(bound), s.max_opt().map(bound))
+        }
+        Statistics::Float(s) => (s.min_opt().map(|v| Bound::Float(*v as f64)), None),
+        Statistics::Double(s) => (s.min_opt().map(|v| Bound::Float(*v)), None),
+        Statistics::ByteArray(s) if *dtype == DataType::String => {
+            let text = |v: &parquet::data_type::ByteArray| {
+                v.as_utf8().ok().map(|text| Bound::Text(text.to_string()))
+            };
+            (s.min_opt().and_then(text), s.max_opt().and_then(text))
+        }
+        _ => (None, None),
+    }
+}
+
+/// Whether the statistics show that no row of a row group matches
+fn can_skip(condition: &Condition, columns: &HashMap<String, ColumnBounds>) -> bool {
+    match condition {
+        Condition::All(conditions) => conditions.iter().any(|c| can_skip(c, columns)),
+        Condition::Any(conditions) => conditions.iter().all(|c| can_skip(c, columns)),
+        Condition::Compare { column, op, value } => columns.get(column).is_some_and(|bounds| {
+            // Comparisons never match nulls
+            bounds.all_null()
+                || match op {
+                    CompareOp::Eq => bounds.outside(value),
+                    CompareOp::NotEq => {
+                        bounds.min.is_some() && bounds.min == bounds.max && bounds.min == *value
+                    }
+                    CompareOp::Lt => bounds.all_above(value, true),
+                    CompareOp::LtEq => bounds.all_above(value, false),
+                    CompareOp::Gt => bounds.all_below(value, true),
+                    CompareOp::GtEq => bounds.all_below(value, false),
+                }
+        }),
+        Condition::Between { column, low, high } => columns.get(column).is_some_and(|bounds| {
+            bounds.all_null() || bounds.all_below(low, false) || bounds.all_above(high, false)
+        }),
+        Condition::In { column, values } => columns.get(column).is_some_and(|bounds| {
+            bounds.all_null() || values.iter().all(|value| bounds.outside(value))
+        }),
+        Condition::IsNull { column } => columns
+            .get(column)
+            .is_some_and(|bounds| bounds.null_count == Some(0)),
+        Condition::IsNotNull { column } | Condition::Matches { column } => {
+            columns.get(column).is_some_and(|bounds| bounds.all_null())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_loader::test_utils::{path_str, temp_dir, write_parquet};
+    use serde_json::json;
+
+    fn skipped(test: &str, filters: serde_json::Value) -> Result<Vec<bool>, String> {
+        let dir = temp_dir(test);
+        let file = dir.join("x.parquet");
+        let mut df = df!(
+            "x" => [0i64, 1, 2, 3, 4, 5],
+            "s" => [Some("a"), Some("b"), None, None, Some("e"), Some("f")],
+        )
+        .unwrap();
+        write_parquet(&file, &mut df, 2);
+
+        let filters: Vec<Filter> = serde_json::from_value(filters).unwrap();
+        let report = row_group_pruning(path_str(&file), df.schema(), &filters)?;
+        Ok(report.details.iter().map(|group| group.skipped).collect())
+    }
+
+    #[test]
+    fn comparisons_skip_row_groups_outside_the_statistics() {
+        let compare = |op: &str, value: serde_json::Value| {
+            let filter = json!([{"kind": "compare", "column": "x", "op": op, "value": value}]);
+            skipped("pruning-compare", filter).unwrap()
+        };
+        assert_eq!(compare("eq", json!(3)), vec![true, false, true]);
+        assert_eq!(compare("lt", json!(2)), vec![false, true, true]);
+        assert_eq!(compare("gt_eq", json!(4)), vec![true, true, false]);
+        // 1.5 is not truncated to 1, which would keep the first row group
+        assert_eq!(compare("gt_eq", json!(1.5)), vec![true, false, false]);
+
+        let between = skipped(
+            "pruning-between",
+            json!([{"kind": "between", "column": "x", "low": 2.5, "high": 3}]),
+        );
+        assert_eq!(between.unwrap(), vec![true, false, true]);
+    }
+
+    #[test]
+    fn null_checks_and_combinations() {
+        let is_null = skipped("pruning-null", json!([{"kind": "is_null", "column": "s"}]));
+        assert_eq!(is_null.unwrap(), vec![true, false, true]);
+        let is_not_null = skipped(
+            "pruning-not-null",
+            json!([{"kind": "is_not_null", "column": "s"}]),
+        );
+        assert_eq!(is_not_null.unwrap(), vec![false, true, false]);
+
+        let any = skipped(
+            "pruning-any",
+            json!([{"kind": "or", "filters": [
+                {"kind": "compare", "column": "x", "op": "eq", "value": 0},
+                {"kind": "in", "column": "x", "values": [5]},
+            ]}]),
+        );
+        assert_eq!(any.unwrap(), vec![false, true, false]);
+    }
+
+    #[test]
+    fn fractional_equality_on_integers_is_rejected() {
+        let eq = skipped(
+            "pruning-eq",
+            json!([{"kind": "compare", "column": "x", "op": "eq", "value": 1.5}]),
+        );
+        assert!(eq.is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use serde::Serialize;
+
+use super::parquet_loader::{dataset_files, read_parquet_metadata};
+use super::parquet_metadata::{statistics_info, StatisticsInfo};
+
+/// A row group and the rows it holds. Row groups are numbered across the
+/// files of a dataset, in the order the files are read.
+#[derive(Serialize)]
+pub struct RowGroupRange {
+    pub row_group: usize,
+    pub path: String,
+    /// Index of the row group within its file
+    pub index: usize,
+    pub first_row: usize,
+    pub num_rows: usize,
+    pub compressed_size: i64,
+    pub columns: Vec<ColumnRange>,
+}
+
+#[derive(Serialize)]
+pub struct ColumnRange {
+    pub path: String,
+    pub statistics: Option<StatisticsInfo>,
+}
+
+/// Where a row of a Parquet file or dataset is stored
+#[derive(Serialize)]
+pub struct RowLocation {
+    /// Row number in the file or dataset
+    pub row: usize,
+    pub row_group: usize,
+    pub path: String,
+    pub index: usize,
+    pub row_in_group: usize,
+}
+
+/// Row groups of every file of a Parquet path, with their row ranges and
+/// column statistics
+pub fn row_groups(file_path: &str) -> Result<Vec<RowGroupRange>, String> {
+    let mut row_groups = Vec::new();
+    let mut first_row = 0;
+
+    for file in dataset_files(file_path)? {
+        let metadata = read_parquet_metadata(&file)?;
+        for (index, row_group) in metadata.row_groups().iter().enumerate() {
+            let num_rows = row_group.num_rows() as usize;
+            row_groups.push(RowGroupRange {
+                row_group: row_groups.len(),
+                path: file.to_string_lossy().to_string(),
+                index,
+                first_row,
+                num_rows,
+                compressed_size: row_group.compressed_size(),
+                columns: row_group
+                    .columns()
+                    .iter()
+                    .map(|column| ColumnRange {
+                        path: column.column_path().string(),
+                        statistics: column.statistics().map(statistics_info),
+                    })
+                    .collect(),
+            });
+            first_row += num_rows;
+        }
+    }
+
+    Ok(row_groups)
+}
+
+/// Where the rows of a row group start, without its column statistics
+#[derive(Clone)]
+pub struct RowGroupOffset {
+    pub path: String,
+    pub index: usize,
+    pub first_row: usize,
+    pub num_rows: usize,
+}
+
+/// Row ranges of the row groups of every file of a Parquet path
+pub fn row_group_offsets(file_path: &str) -> Result<Vec<RowGroupOffset>, String> {
+    let mut offsets = Vec::new();
+    let mut first_row = 0;
+
+    for file in dataset_files(file_path)? {
+        let metadata = read_parquet_metadata(&file)?;
+        for (index, row_group) in metadata.row_groups().iter().enumerate() {
+            let num_rows = row_group.num_rows() as usize;
+            offsets.push(RowGroupOffset {
+                path: file.to_string_lossy().to_string(),
+                index,
+                first_row,
+                num_rows,
+            });
+            first_row += num_rows;
+        }
+    }
+
+    Ok(offsets)
+}
+
+/// First row and number of rows of a row group
+pub fn row_group_range(
+    offsets: &[RowGroupOffset],
+    row_group: usize,
+) -> Result<(usize, usize), String> {
+    let offset = offsets.get(row_group).ok_or_else(|| {
+        format!(
+            "Row group {} not found, the file has {}",
+            row_group,
+            offsets.len()
+        )
+    })?;
+
+    Ok((offset.first_row, offset.num_rows))
+}
+
+/// Row group that holds a row of a Parquet file or dataset
+pub fn locate_row_group(offsets: &[RowGroupOffset], row: usize) -> Result<RowLocation, String> {
+    offsets
+        .iter()
+        .enumerate()
+        .find(|(_, offset)| row < offset.first_row + offset.num_rows)
+        .map(|(row_group, offset)| RowLocation {
+            row,
+            row_group,
+            row_in_group: row - offset.first_row,
+            path: offset.path.clone(),
+            index: offset.index,
+        })
+        .ok_or_else(|| format!("Row {} not found", row))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_loader::test_utils::{path_str, temp_dir, write_parquet};
+    use polars::prelude::*;
+
+    #[test]
+    fn rows_are_located_across_the_files_of_a_dataset() {
+        let dir = temp_dir("row-groups-dataset");
+        for (name, rows) in [("part-0.parquet", 0..4i64), ("part-1.parquet", 4..6i64)] {
+            let mut df = df!("a" => rows.collect::<Vec<_>>()).unwrap();
+            write_parquet(&dir.join(name), &mut df, 2);
+        }
+
+        let offsets = row_group_offsets(path_str(&dir)).unwrap();
+        let ranges: Vec<_> = offsets.iter().map(|o| (o.first_row, o.num_rows)).collect();
+        assert_eq!(ranges, vec![(0, 2), (2, 2), (4, 2)]);
+        assert_eq!(row_group_range(&offsets, 1).unwrap(), (2, 2));
+        assert!(row_group_range(&offsets, 3).is_err());
+
+        let location = locate_row_group(&offsets, 5).unwrap();
+        assert_eq!(
+            (location.row_group, location.index, location.row_in_group),
+            (2, 0, 1)
+        );
+        assert!(location.path.ends_with("part-1.parquet"));
+        assert!(locate_row_group(&offsets, 6).is_err());
+    }
+}
@@ -0,0 +1,321 @@
+use parquet::basic::{ConvertedType, Repetition};
+use parquet::schema::types::Type;
+use polars::prelude::*;
+use serde::Serialize;
+
+use super::format_registry::{resolve_format, FormatKind};
+use super::parquet_loader::{dataset_files, read_parquet_metadata};
+
+/// A field of the schema with its nested fields. Parquet details are only set
+/// for Parquet files; fields without a Polars dtype exist only in the Parquet
+/// schema.
+#[derive(Serialize)]
+pub struct SchemaNode {
+    pub name: String,
+    /// Dot-separated path from the root, as used for Parquet column chunks
+    pub path: String,
+    pub dtype: Option<String>,
+    pub physical_type: Option<String>,
+    pub logical_type: Option<String>,
+    pub converted_type: Option<String>,
+    pub repetition: Option<String>,
+    pub nullable: bool,
+    pub field_id: Option<i32>,
+    /// Byte length of fixed-length byte arrays
+    pub type_length: Option<i32>,
+    pub precision: Option<i32>,
+    pub scale: Option<i32>,
+    pub children: Vec<SchemaNode>,
+}
+
+/// Build the schema tree of a file from the Polars schema it is read with.
+/// For Parquet files the fields are matched with the Parquet schema of the
+/// first file of the dataset.
+pub fn schema_tree(file_path: &str, schema: &Schema) -> Result<Vec<SchemaNode>, String> {
+    let root = if resolve_format(file_path)?.kind == FormatKind::Parquet {
+        let files = dataset_files(file_path)?;
+        match files.first() {
+            Some(file) => Some(read_parquet_metadata(file)?),
+            None => None,
+        }
+    } else {
+        None
+    };
+    let root_fields = root
+        .as_ref()
+        .map(|metadata| metadata.file_metadata().schema().get_fields())
+        .unwrap_or_default();
+
+    Ok(schema
+        .iter()
+        .map(|(name, dtype)| {
+            // Hive partition columns are not stored in the files
+            match root_fields
+                .iter()
+                .find(|field| field.name() == name.as_str())
+            {
+                Some(field) => parquet_node(field, Some(dtype), ""),
+                None => polars_node(name, dtype, ""),
+            }
+        })
+        .collect())
+}
+
+fn polars_node(name: &str, dtype: &DataType, parent_path: &str) -> SchemaNode {
+    let path = child_path(parent_path, name);
+    let children = match dtype {
+        DataType::Struct(fields) => fields
+            .iter()
+            .map(|field| polars_node(field.name(), field.dtype(), &path))
+            .collect(),
+        DataType::List(inner) | DataType::Array(inner, _) => {
+            vec![polars_node("item", inner, &path)]
+        }
+        _ => Vec::new(),
+    };
+
+    SchemaNode {
+        name: name.to_string(),
+        dtype: Some(format!("{:?}", dtype)),
+        physical_type: None,
+        logical_type: None,
+        converted_type: None,
+        repetition: None,
+        nullable: true,
+        field_id: None,
+        type_length: None,
+        precision: None,
+        scale: None,
+        children,
+        path,
+    }
+}
+
+fn parquet_node(field: &Type, dtype: Option<&DataType>, parent_path: &str) -> SchemaNode {
+    let info = field.get_basic_info();
+    let path = child_path(parent_path, field.name());
+
+    let (physical_type, type_length, precision, scale) = match field {
+        Type::PrimitiveType {
+            physical_type,
+            type_length,
+            precision,
+            scale,
+            ..
+        } => (
+            Some(physical_type.to_string()),
+            Some(*type_length).filter(|length| *length >= 0),
+            Some(*precision).filter(|precision| *precision >= 0),
+            Some(*scale).filter(|scale| *scale >= 0),
+        ),
+        Type::GroupType { .. } => (None, None, None, None),
+    };
+    let repetition = info.has_repetition().then(|| info.repetition());
+
+    let children = match field {
+        Type::GroupType { fields, .. } => fields
+            .iter()
+            .map(|child| parquet_node(child, child_dtype(field, child, dtype), &path))
+            .collect(),
+        Type::PrimitiveType { .. } => Vec::new(),
+    };
+
+    SchemaNode {
+        name: field.name().to_string(),
+        dtype: dtype.map(|dtype| format!("{:?}", dtype)),
+        physical_type,
+        logical_type: info.logical_type_ref().map(|t| format!("{:?}", t)),
+        converted_type: Some(info.converted_type())
+            .filter(|t| *t != ConvertedType::NONE)
+            .map(|t| t.to_string()),
+        repetition: repetition.map(|r| r.to_string()),
+        nullable: repetition != Some(Repetition::REQUIRED),
+        field_id: info.has_id().then(|| info.id()),
+        type_length,
+        precision,
+        scale,
+        children,
+        path,
+    }
+}
+
+/// Polars dtype of a nested Parquet field. The repeated group between a list
+/// and its elements keeps the dtype of the list.
+fn child_dtype<'a>(
+    parent: &Type,
+    child: &Type,
+    dtype: Option<&'a DataType>,
+) -> Option<&'a DataType> {
+    match dtype? {
+        DataType::Struct(fields) => fields
+            .iter()
+            .find(|field| field.name() == child.name())
+            .map(|field| field.dtype()),
+        DataType::List(inner) | DataType::Array(inner, _) => {
+            if is_list_element(parent, child) {
+                Some(inner)
+            } else {
+                dtype
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Whether a field holds the elements of a list, following the backward
+/// compatibility rules of the Parquet format for two- and three-level lists
+fn is_list_element(parent: &Type, child: &Type) -> bool {
+    let info = child.get_basic_info();
+    if !info.has_repetition() || info.repetition() != Repetition::REPEATED {
+        // Element of a three-level list, below the repeated group
+        return true;
+    }
+
+    child.is_primitive()
+        || child.get_fields().len() > 1
+        || child.name() == "array"
+        || child.name() == format!("{}_tuple", parent.name())
+}
+
+fn child_path(parent_path: &str, name: &str) -> String {
+    if parent_path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", parent_path, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_loader::test_utils::{path_str, temp_dir, write_parquet};
+    use parquet::schema::parser::parse_message_type;
+
+    fn file_tree(dir: &str, mut df: DataFrame) -> Vec<SchemaNode> {
+        let path = temp_dir(dir).join("nested.parquet");
+        write_parquet(&path, &mut df, 10);
+        schema_tree(path_str(&path), df.schema()).unwrap()
+    }
+
+    #[test]
+    fn struct_fields_get_the_dtypes_of_the_struct() {
+        let df = df!("a" => [1i64, 2], "b" => ["x", "y"])
+            .unwrap()
+            .lazy()
+            .select([as_struct(vec![col("a"), col("b")]).alias("s")])
+            .collect()
+            .unwrap();
+        let tree = file_tree("schema-struct", df);
+
+        let s = &tree[0];
+        assert_eq!(s.path, "s");
+        assert_eq!(s.physical_type, None);
+        let fields: Vec<_> = s
+            .children
+            .iter()
+            .map(|c| {
+                (
+                    c.path.as_str(),
+                    c.dtype.as_deref(),
+                    c.physical_type.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("s.a", Some("Int64"), Some("INT64")),
+                ("s.b", Some("String"), Some("BYTE_ARRAY"))
+            ]
+        );
+    }
+
+    #[test]
+    fn list_elements_get_the_inner_dtype_at_every_level() {
+        let inner = |values: &[i64]| Series::new(PlSmallStr::EMPTY, values);
+        let outer = |lists: &[Series]| Series::new(PlSmallStr::EMPTY, lists);
+        let df = df!(
+            "l" => [outer(&[inner(&[1, 2]), inner(&[3])]), outer(&[inner(&[4])])]
+        )
+        .unwrap();
+        let tree = file_tree("schema-list", df);
+
+        // l (LIST) > repeated group > element (LIST) > repeated group > leaf
+        let mut levels = Vec::new();
+        let mut node = &tree[0];
+        loop {
+            levels.push((node.repetition.clone(), node.dtype.clone()));
+            match node.children.as_slice() {
+                [child] => node = child,
+                [] => break,
+                _ => panic!("list levels have one child"),
+            }
+        }
+        let dtypes: Vec<_> = levels.iter().map(|(_, d)| d.as_deref().unwrap()).collect();
+        assert_eq!(
+            dtypes,
+            [
+                "List(List(Int64))",
+                "List(List(Int64))",
+                "List(Int64)",
+                "List(Int64)",
+                "Int64"
+            ]
+        );
+        assert_eq!(levels[1].0.as_deref(), Some("REPEATED"));
+        assert_eq!(levels[3].0.as_deref(), Some("REPEATED"));
+        assert_eq!(node.physical_type.as_deref(), Some("INT64"));
+    }
+
+    #[test]
+    fn two_level_lists_follow_the_compatibility_rules() {
+        let schema = parse_message_type(
+            "message schema {
+                optional group l (LIST) {
+                    repeated int32 element;
+                }
+                optional group s (LIST) {
+                    repeated group array {
+                        required int32 x;
+                    }
+                }
+            }",
+        )
+        .unwrap();
+        let fields = schema.get_fields();
+        let list = DataType::List(Box::new(DataType::Int32));
+        let node = parquet_node(&fields[0], Some(&list), "");
+        assert_eq!(node.children[0].dtype.as_deref(), Some("Int32"));
+
+        // A repeated group named `array` is the element itself
+        let structs = DataType::List(Box::new(DataType::Struct(vec![Field::new(
+            "x".into(),
+            DataType::Int32,
+        )])));
+        let node = parquet_node(&fields[1], Some(&structs), "");
+        let element = &node.children[0];
+        assert_eq!(element.dtype.as_deref(), Some("Struct({'x': Int32})"));
+        assert_eq!(element.children[0].dtype.as_deref(), Some("Int32"));
+    }
+
+    #[test]
+    fn decimals_report_precision_and_scale() {
+        let price = Series::new("price".into(), [1.5f64, 20.25])
+            .cast(&DataType::Decimal(10, 2))
+            .unwrap();
+        let tree = file_tree(
+            "schema-decimal",
+            DataFrame::new(vec![price.into()]).unwrap(),
+        );
+
+        let node = &tree[0];
+        assert_eq!(node.dtype.as_deref(), Some("Decimal(10, 2)"));
+        assert_eq!((node.precision, node.scale), (Some(10), Some(2)));
+        assert_eq!(node.converted_type.as_deref(), Some("DECIMAL"));
+        assert!(node
+            .logical_type
+            .as_deref()
+            .is_some_and(|t| t.contains("Decimal") && t.contains("precision: 10")));
+        assert!(node.children.is_empty());
+    }
+}
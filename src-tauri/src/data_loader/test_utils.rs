@@ -0,0 +1,25 @@
+use polars::prelude::*;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+/// Empty directory for the files of one test
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("pinkparquet-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Write a DataFrame to a Parquet file with row groups of at most
+/// `row_group_size` rows
+pub fn write_parquet(path: &Path, df: &mut DataFrame, row_group_size: usize) {
+    ParquetWriter::new(File::create(path).unwrap())
+        .with_row_group_size(Some(row_group_size))
+        .with_statistics(StatisticsOptions::full())
+        .finish(df)
+        .unwrap();
+}
+
+pub fn path_str(path: &Path) -> &str {
+    path.to_str().unwrap()
+}
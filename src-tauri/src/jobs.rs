@@ -0,0 +1,328 @@
+use polars::prelude::*;
+use serde::Serialize;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// Error returned by a job that was cancelled
+pub const JOB_CANCELLED: &str = "Job cancelled";
+
+/// How often a job checks whether it was cancelled while a query runs
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How often a running job reports its status
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// Payload of the "job-status" event
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobStatus {
+    pub job_id: String,
+    pub command: String,
+    pub state: JobState,
+    pub elapsed_ms: u64,
+    pub error: Option<String>,
+}
+
+/// A command running on behalf of the frontend. Queries collected with the
+/// in-memory engine are interrupted when the job is cancelled, streaming
+/// queries at the next batch read through `cancellable`.
+pub struct Job {
+    id: String,
+    command: &'static str,
+    /// Receives the status events; jobs run without one in tests
+    app_handle: Option<AppHandle>,
+    started: Instant,
+    cancelled: Arc<AtomicBool>,
+    /// In-memory query being collected, interrupted when the job is cancelled
+    query: Mutex<Option<InProcessQuery>>,
+}
+
+impl Job {
+    fn new(id: String, command: &'static str, app_handle: Option<AppHandle>) -> Self {
+        Self {
+            id,
+            command,
+            app_handle,
+            started: Instant::now(),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            query: Mutex::new(None),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Fail with `JOB_CANCELLED` once the job is cancelled. Commands call this
+    /// before updating the cache so cancelled jobs leave it untouched.
+    pub fn check_cancelled(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(JOB_CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Collect a LazyFrame with the in-memory engine. Polars checks for
+    /// cancellation between the steps of the plan, so a cancelled query stops
+    /// at the next step; the job returns without waiting for it.
+    pub fn collect(&self, lf: LazyFrame) -> Result<DataFrame, String> {
+        self.check_cancelled()?;
+
+        let query = lf
+            .collect_concurrently()
+            .map_err(|e| format!("Failed to collect DataFrame: {}", e))?;
+        let waiter = query.clone();
+        *self.query.lock().unwrap() = Some(query);
+
+        // The job may have been cancelled before the query was registered
+        if self.is_cancelled() {
+            self.cancel_query();
+        }
+
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(waiter.fetch_blocking());
+        });
+
+        let result = self.wait(rx);
+        self.query.lock().unwrap().take();
+        result
+    }
+
+    /// Check for cancellation on every batch read from `lf`. The streaming
+    /// engine cannot be interrupted from outside, so queries collected with
+    /// `collect_streaming` are built on a view wrapped by this; they fail at
+    /// the next batch once the job is cancelled.
+    pub fn cancellable(&self, lf: LazyFrame) -> LazyFrame {
+        let cancelled = self.cancelled.clone();
+        lf.map(
+            move |df| {
+                if cancelled.load(Ordering::SeqCst) {
+                    polars_bail!(ComputeError: "{}", JOB_CANCELLED);
+                }
+                Ok(df)
+            },
+            AllowedOptimizations::default() | OptFlags::NEW_STREAMING,
+            None,
+            Some("CANCELLATION CHECK"),
+        )
+    }
+
+    /// Collect a LazyFrame with the streaming engine. A cancelled job stops
+    /// waiting right away; the query itself stops at the next batch of a view
+    /// wrapped by `cancellable`.
+    pub fn collect_streaming(&self, lf: LazyFrame) -> Result<DataFrame, String> {
+        self.check_cancelled()?;
+
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(lf.collect_with_engine(Engine::Streaming));
+        });
+
+        self.wait(rx)
+    }
+
+    fn wait(&self, rx: mpsc::Receiver<PolarsResult<DataFrame>>) -> Result<DataFrame, String> {
+        let mut last_report = Instant::now();
+
+        let result = loop {
+            match rx.recv_timeout(POLL_INTERVAL) {
+                Ok(result) => break result,
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err("Query stopped unexpectedly".to_string())
+                }
+            }
+
+            self.check_cancelled()?;
+
+            if last_report.elapsed() >= PROGRESS_INTERVAL {
+                self.emit(JobState::Running, None);
+                last_report = Instant::now();
+            }
+        };
+
+        self.check_cancelled()?;
+        result.map_err(|e| format!("Failed to collect DataFrame: {}", e))
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.cancel_query();
+    }
+
+    fn cancel_query(&self) {
+        if let Some(query) = self.query.lock().unwrap().as_ref() {
+            query.cancel();
+        }
+    }
+
+    fn emit(&self, state: JobState, error: Option<String>) {
+        let status = JobStatus {
+            job_id: self.id.clone(),
+            command: self.command.to_string(),
+            state,
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            error,
+        };
+        if let Some(app_handle) = &self.app_handle {
+            let _ = app_handle.emit("job-status", status);
+        }
+    }
+}
+
+/// Jobs that are currently running, by ID
+#[derive(Default)]
+pub struct JobRegistry {
+    running: Mutex<HashMap<String, Arc<Job>>>,
+    next_id: AtomicU64,
+}
+
+impl JobRegistry {
+    /// Run a command as a job. The frontend may pass its own ID so it can cancel
+    /// the job before the command returns; otherwise one is generated. Status
+    /// events are emitted when the job starts, while it runs and when it ends.
+    /// An ID that is already running is rejected, so each job stays cancellable.
+    pub fn run<T>(
+        &self,
+        app_handle: &AppHandle,
+        command: &'static str,
+        job_id: Option<String>,
+        f: impl FnOnce(&Job) -> Result<T, String>,
+    ) -> Result<T, String> {
+        self.run_job(Some(app_handle.clone()), command, job_id, f)
+    }
+
+    fn run_job<T>(
+        &self,
+        app_handle: Option<AppHandle>,
+        command: &'static str,
+        job_id: Option<String>,
+        f: impl FnOnce(&Job) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let id = job_id
+            .unwrap_or_else(|| format!("job-{}", self.next_id.fetch_add(1, Ordering::Relaxed)));
+        let job = Arc::new(Job::new(id.clone(), command, app_handle));
+
+        match self.running.lock().unwrap().entry(id.clone()) {
+            Entry::Occupied(_) => return Err(format!("Job {} is already running", id)),
+            Entry::Vacant(entry) => {
+                entry.insert(job.clone());
+            }
+        }
+        job.emit(JobState::Running, None);
+
+        let result = f(&job);
+
+        self.running.lock().unwrap().remove(&id);
+        match &result {
+            Ok(_) => job.emit(JobState::Completed, None),
+            Err(_) if job.is_cancelled() => job.emit(JobState::Cancelled, None),
+            Err(e) => job.emit(JobState::Failed, Some(e.clone())),
+        }
+
+        result
+    }
+
+    /// Cancel a running job. Returns false if no job with this ID is running.
+    pub fn cancel(&self, job_id: &str) -> bool {
+        match self.running.lock().unwrap().get(job_id) {
+            Some(job) => {
+                job.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancelled_job_returns_before_its_query_finishes() {
+        let registry = Arc::new(JobRegistry::default());
+        let (started_tx, started_rx) = mpsc::channel();
+
+        let jobs = registry.clone();
+        let handle = thread::spawn(move || {
+            jobs.run_job(None, "test", Some("slow".to_string()), |job| {
+                let lf = job
+                    .cancellable(df!("x" => [1i64, 2, 3]).unwrap().lazy())
+                    .map(
+                        |df| {
+                            thread::sleep(Duration::from_secs(5));
+                            Ok(df)
+                        },
+                        AllowedOptimizations::default() | OptFlags::NEW_STREAMING,
+                        None,
+                        None,
+                    );
+                started_tx.send(()).unwrap();
+                job.collect_streaming(lf)
+            })
+        });
+
+        started_rx.recv().unwrap();
+        let cancelled = Instant::now();
+        assert!(registry.cancel("slow"));
+        assert_eq!(handle.join().unwrap().unwrap_err(), JOB_CANCELLED);
+        assert!(cancelled.elapsed() < Duration::from_secs(2));
+        // The job is gone once it returned
+        assert!(!registry.cancel("slow"));
+    }
+
+    #[test]
+    fn streaming_queries_check_the_cancel_flag() {
+        let job = Job::new("streaming".to_string(), "test", None);
+        let lf = || job.cancellable(df!("x" => [1i64, 2, 3]).unwrap().lazy());
+        let sum = |lf: LazyFrame| {
+            lf.select([col("x").sum()])
+                .collect_with_engine(Engine::Streaming)
+        };
+
+        assert_eq!(
+            sum(lf()).unwrap().column("x").unwrap().get(0).unwrap(),
+            AnyValue::Int64(6)
+        );
+        job.cancel();
+        assert!(sum(lf()).unwrap_err().to_string().contains(JOB_CANCELLED));
+    }
+
+    #[test]
+    fn duplicate_job_ids_are_rejected() {
+        let registry = JobRegistry::default();
+        let result = registry.run_job(None, "outer", Some("a".to_string()), |outer| {
+            let inner = registry.run_job(None, "inner", Some("a".to_string()), |_| Ok(()));
+            assert_eq!(inner.unwrap_err(), "Job a is already running");
+
+            // The running job keeps its cancel flag
+            assert!(registry.cancel("a"));
+            assert!(outer.is_cancelled());
+            outer.check_cancelled()
+        });
+        assert_eq!(result.unwrap_err(), JOB_CANCELLED);
+
+        // The ID is free again once the job ended
+        assert!(registry
+            .run_job(None, "again", Some("a".to_string()), |_| Ok(()))
+            .is_ok());
+    }
+}
@@ -5,36 +5,56 @@ use polars::sql::SQLContext;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::{Path, PathBuf};
-use std::sync::Mutex;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter, Manager, Window};
 
+mod cache;
 mod data_loader;
+mod jobs;
+mod profile;
+mod query_plan;
+mod sql_params;
+mod sql_session;
+use cache::{CacheKey, CacheUsage, CachedData, ViewCache, DEFAULT_CACHE_BUDGET_MB, ROW_INDEX};
 use data_loader::{
-    apply_sorts, calculate_statistics, dataframe_to_rows, open_csv, open_parquet, Sorting,
+    apply_filters, apply_sorts, calculate_correlations, calculate_histogram_from_dataframe,
+    calculate_pair_density, calculate_statistics_lazy, column_pages, count_rows_and_nulls,
+    dataframe_to_rows, dataset_files, dataset_root, footer_counts, locate_row_group, open_file,
+    parquet_footers, resolve_format, row_group_offsets, row_group_pruning, row_group_range,
+    row_groups, schema_tree, select_numeric, view_footer_statistics, ColumnPages,
+    CorrelationMatrix, CorrelationMethod, CsvOptions, Filter, FormatKind, HistogramData,
+    HistogramOptions, PairDensity, ParquetFooter, PruningReport, ReadOptions, RowGroupOffset,
+    RowGroupRange, RowLocation, SchemaNode, Sorting,
+};
+use jobs::{Job, JobRegistry};
+use profile::{profile_lazy, write_report};
+use query_plan::{explain_query, QueryPlan};
+use sql_params::{bind_parameters, SqlParameter};
+use sql_session::{
+    broken_view_error, parse_session_statement, BrokenView, SessionStatement, SessionTableInfo,
+    SessionTableKind, SqlSession,
 };
 
 pub struct AppState {
-    cache: Mutex<Option<CacheEntry>>,
+    cache: Mutex<ViewCache>,
+    jobs: JobRegistry,
     watchers: Mutex<HashMap<String, RecommendedWatcher>>,
+    /// SQL sessions by window label
+    sql_sessions: Mutex<HashMap<String, SqlSession>>,
 }
 
 impl Default for AppState {
     fn default() -> Self {
         Self {
-            cache: Mutex::new(None),
+            cache: Mutex::new(ViewCache::default()),
+            jobs: JobRegistry::default(),
             watchers: Mutex::new(HashMap::new()),
+            sql_sessions: Mutex::new(HashMap::new()),
         }
     }
 }
 
-struct CacheEntry {
-    file_path: Option<String>,
-    sorting: Option<Vec<Sorting>>,
-    query: Option<String>,
-    df: DataFrame,
-}
-
 #[derive(Serialize)]
 struct ColumnInfo {
     name: String,
@@ -47,11 +67,45 @@ struct MetadataInfo {
     created: Option<String>,
     modified: Option<String>,
     size: u64,
+    file_count: usize,
     row_groups: usize,
     compression: String,
     total_nulls: usize,
 }
 
+#[derive(Serialize)]
+struct Correlations {
+    pearson: CorrelationMatrix,
+    spearman: CorrelationMatrix,
+}
+
+// A view named by the frontend: a file with the options it is shown with, or a SQL query with the
+// tables and values it ran with
+#[derive(Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum ViewRef {
+    File {
+        #[serde(rename = "filePath")]
+        file_path: String,
+        #[serde(rename = "csvOptions")]
+        csv_options: Option<CsvOptions>,
+        filters: Option<Vec<Filter>>,
+        sorting: Option<Vec<Sorting>>,
+        #[serde(rename = "rowGroup")]
+        row_group: Option<usize>,
+    },
+    Query {
+        query: String,
+        #[serde(default, rename = "allFiles")]
+        all_files: Vec<String>,
+        #[serde(rename = "tableNames")]
+        table_names: Option<HashMap<String, String>>,
+        #[serde(rename = "csvOptions")]
+        csv_options: Option<HashMap<String, CsvOptions>>,
+        parameters: Option<HashMap<String, SqlParameter>>,
+    },
+}
+
 #[derive(Serialize)]
 struct DataFrameInfo {
     shape: (usize, usize),
@@ -61,10 +115,9 @@ struct DataFrameInfo {
 }
 
 fn table_name_from_path(path: &str) -> String {
-    let stem = std::path::Path::new(path)
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("table");
+    // Globs are named after the directory they are rooted at
+    let root = dataset_root(path);
+    let stem = root.file_stem().and_then(|s| s.to_str()).unwrap_or("table");
     let mut s = String::with_capacity(stem.len() + 1);
 
     for (i, ch) in stem.chars().enumerate() {
@@ -104,321 +157,837 @@ fn execute_sql_query(
         .map_err(|e| format!("SQL execution error: {}", e))
 }
 
-// Get initial data with metadata (returns first 100 rows)
-#[tauri::command]
-fn get_data(
-    state: tauri::State<AppState>,
-    file_path: String,
-    sorting: Option<Vec<Sorting>>,
-) -> Result<DataFrameInfo, String> {
-    // Check cache
-    {
-        let cache = state.cache.lock().unwrap();
-        if let Some(entry) = cache.as_ref() {
-            if entry.file_path.as_deref() == Some(&file_path)
-                && entry.sorting == sorting
-                && entry.query.is_none()
-            {
-                let df = &entry.df;
-                let shape = df.shape();
-                let columns = extract_columns(df);
-
-                let n = shape.0.min(100);
-                let df_head = df.head(Some(n));
-                let rows = dataframe_to_rows(&df_head)?;
-                let mut metadata = extract_metadata(&file_path)?;
-                metadata.total_nulls = calculate_total_nulls(df);
-
-                return Ok(DataFrameInfo {
-                    shape,
-                    columns,
-                    rows,
-                    metadata: Some(metadata),
-                });
+// Read options for one of the files passed to a command
+fn read_options_for(path: &str, csv_options: &Option<HashMap<String, CsvOptions>>) -> ReadOptions {
+    ReadOptions {
+        csv: csv_options
+            .as_ref()
+            .and_then(|options| options.get(path).cloned()),
+    }
+}
+
+// Key single-file CSV options by path so they compare with cached entries
+fn csv_options_by_path(
+    file_path: &str,
+    csv_options: Option<CsvOptions>,
+) -> Option<HashMap<String, CsvOptions>> {
+    csv_options.map(|options| HashMap::from([(file_path.to_string(), options)]))
+}
+
+// Register all open files as tables in a new SQL context
+fn build_sql_context(
+    all_files: &[String],
+    table_names: &Option<HashMap<String, String>>,
+    csv_options: &Option<HashMap<String, CsvOptions>>,
+) -> Result<SQLContext, String> {
+    let mut ctx = SQLContext::new();
+
+    for path in all_files {
+        let lf = open_file(path, &read_options_for(path, csv_options))?;
+        let table_name = if let Some(ref names) = table_names {
+            names
+                .get(path)
+                .cloned()
+                .unwrap_or_else(|| table_name_from_path(path))
+        } else {
+            table_name_from_path(path)
+        };
+        ctx.register(&table_name, lf);
+    }
+
+    Ok(ctx)
+}
+
+// Register the open files, then the views and tables of the window's SQL session. Views that
+// cannot be planned are left out and returned, to name them in the errors of the queries that
+// read them.
+fn build_session_context(
+    state: &AppState,
+    window: &Window,
+    all_files: &[String],
+    table_names: &Option<HashMap<String, String>>,
+    csv_options: &Option<HashMap<String, CsvOptions>>,
+) -> Result<(SQLContext, Vec<BrokenView>), String> {
+    let mut ctx = build_sql_context(all_files, table_names, csv_options)?;
+    // Plan the views on a copy of the session, so other windows are not blocked meanwhile
+    let session = state
+        .sql_sessions
+        .lock()
+        .unwrap()
+        .get(window.label())
+        .cloned();
+    let broken = session
+        .map(|session| session.register(&mut ctx))
+        .unwrap_or_default();
+    Ok((ctx, broken))
+}
+
+// Plan a query in a session context, naming the broken view it reads if it fails
+fn execute_session_query(
+    ctx: &mut SQLContext,
+    broken: &[BrokenView],
+    query: &str,
+) -> Result<LazyFrame, String> {
+    execute_sql_query(ctx, query).map_err(|e| broken_view_error(query, e, broken))
+}
+
+// Create or drop a view or temporary table in the SQL session of a window. Temporary tables
+// are collected now; views are planned again whenever a query reads them.
+fn run_session_statement(
+    state: &AppState,
+    window: &Window,
+    job: &Job,
+    statement: &SessionStatement,
+    all_files: &[String],
+    table_names: &Option<HashMap<String, String>>,
+    csv_options: &Option<HashMap<String, CsvOptions>>,
+) -> Result<(), String> {
+    match statement {
+        SessionStatement::Create {
+            name,
+            kind,
+            or_replace,
+            query,
+        } => {
+            let (mut ctx, broken) =
+                build_session_context(state, window, all_files, table_names, csv_options)?;
+            let mut lf = execute_session_query(&mut ctx, &broken, query)?;
+            let data = match kind {
+                SessionTableKind::View => {
+                    // Fail now rather than when the view is used
+                    lf.collect_schema()
+                        .map_err(|e| format!("SQL execution error: {}", e))?;
+                    None
+                }
+                SessionTableKind::Table => {
+                    let df = job.collect(lf)?;
+                    check_table_budget(state, window, name, *or_replace, &df)?;
+                    Some(df)
+                }
+            };
+
+            job.check_cancelled()?;
+            state
+                .sql_sessions
+                .lock()
+                .unwrap()
+                .entry(window.label().to_string())
+                .or_default()
+                .create(name, *kind, *or_replace, query, data)?;
+        }
+        SessionStatement::Drop { name, if_exists } => {
+            let dropped = state
+                .sql_sessions
+                .lock()
+                .unwrap()
+                .get_mut(window.label())
+                .is_some_and(|session| session.drop_table(name));
+            if !dropped && !if_exists {
+                return Err(format!("Table not found: {}", name));
             }
         }
     }
 
-    // Open file
-    let mut lf = if file_path.to_lowercase().ends_with(".csv") {
-        open_csv(&file_path)?
-    } else {
-        open_parquet(&file_path)?
-    };
+    // Cached queries may read the views and tables that changed
+    state.cache.lock().unwrap().evict_queries();
+    Ok(())
+}
 
-    // Apply sorts if provided
-    if let Some(sorts) = sorting.clone() {
-        lf = apply_sorts(lf, sorts)?;
+// Temporary tables of all windows together hold at most the cache budget. The table that
+// CREATE OR REPLACE replaces is not counted.
+fn check_table_budget(
+    state: &AppState,
+    window: &Window,
+    name: &str,
+    or_replace: bool,
+    df: &DataFrame,
+) -> Result<(), String> {
+    let budget_bytes = state.cache.lock().unwrap().budget_bytes();
+    let used: usize = state
+        .sql_sessions
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(label, session)| {
+            let replacing = (or_replace && label == window.label()).then_some(name);
+            session.table_bytes(replacing)
+        })
+        .sum();
+
+    let size = df.estimated_size();
+    if used + size > budget_bytes {
+        return Err(format!(
+            "Table {} needs {} MB, more than the {} MB left of the cache budget of {} MB; drop a \
+             table or add a LIMIT",
+            name,
+            size.div_ceil(1024 * 1024),
+            budget_bytes.saturating_sub(used) / (1024 * 1024),
+            budget_bytes / (1024 * 1024)
+        ));
     }
+    Ok(())
+}
 
-    // Collect DataFrame
-    let df = lf
-        .collect()
-        .map_err(|e| format!("Failed to collect DataFrame: {}", e))?;
+// Open a file view. Sorting needs every row, so sorted views are collected; unsorted views
+// are paged lazily from the file. A scan keeps the order of the file, even when filtered, so
+// pages neither overlap nor skip rows.
+fn load_file_view(
+    state: &AppState,
+    job: &Job,
+    file_path: &str,
+    filters: &Option<Vec<Filter>>,
+    sorting: &Option<Vec<Sorting>>,
+    csv_options: &Option<HashMap<String, CsvOptions>>,
+    row_group: Option<usize>,
+) -> Result<CachedData, String> {
+    let filtered = filters.as_ref().is_some_and(|filters| !filters.is_empty());
+    let sorted = sorting.as_ref().is_some_and(|sorts| !sorts.is_empty());
+    let parquet = resolve_format(file_path)?.kind == FormatKind::Parquet;
+
+    let mut lf = open_file(file_path, &read_options_for(file_path, csv_options))?;
+    // Keep the row number in the file, so locate_row can find the row group of a row
+    if parquet && (filtered || sorted) {
+        lf = lf.with_row_index(ROW_INDEX, None);
+    }
+    let mut lf = slice_row_group(state, lf, file_path, row_group)?;
 
-    // Update cache
-    {
-        let mut cache = state.cache.lock().unwrap();
-        *cache = Some(CacheEntry {
-            file_path: Some(file_path.clone()),
-            sorting,
-            query: None,
-            df: df.clone(),
-        });
+    if let Some(filters) = filters {
+        lf = apply_filters(lf, filters.clone())?;
     }
 
-    let shape = df.shape();
+    if let Some(sorts) = sorting.as_ref().filter(|sorts| !sorts.is_empty()) {
+        let df = job.collect(apply_sorts(lf, sorts.clone())?)?;
+        return Ok(CachedData::Collected(df));
+    }
+
+    // Parquet footers hold row and null counts, so no data has to be read
+    if !filtered && row_group.is_none() && parquet {
+        let counts = footer_counts(file_path)?;
+        if let Some(total_nulls) = counts.nulls {
+            return Ok(CachedData::Paged {
+                lf: Box::new(lf),
+                height: counts.rows,
+                total_nulls,
+            });
+        }
+    }
+
+    let (height, total_nulls) = count_rows_and_nulls(&job.cancellable(lf.clone()), |counts| {
+        job.collect_streaming(counts)
+    })?;
+    Ok(CachedData::Paged {
+        lf: Box::new(lf),
+        height,
+        total_nulls,
+    })
+}
 
-    // Get column information
-    let columns = extract_columns(&df);
+// Restrict a file to one of its Parquet row groups. Sliced Parquet scans only read the row
+// groups that overlap the slice.
+fn slice_row_group(
+    state: &AppState,
+    lf: LazyFrame,
+    file_path: &str,
+    row_group: Option<usize>,
+) -> Result<LazyFrame, String> {
+    match row_group {
+        Some(row_group) => {
+            let offsets = cached_row_group_offsets(state, file_path)?;
+            let (first_row, num_rows) = row_group_range(&offsets, row_group)?;
+            Ok(lf.slice(first_row as i64, num_rows as u32))
+        }
+        None => Ok(lf),
+    }
+}
 
-    // Get first 100 rows
-    let n = shape.0.min(100);
-    let df_head = df.head(Some(n));
-    let rows = dataframe_to_rows(&df_head)?;
+// Row group offsets of a Parquet path, read from the footers once and kept in the cache until
+// the file changes
+fn cached_row_group_offsets(
+    state: &AppState,
+    file_path: &str,
+) -> Result<Arc<Vec<RowGroupOffset>>, String> {
+    if let Some(offsets) = state.cache.lock().unwrap().row_group_offsets(file_path) {
+        return Ok(offsets);
+    }
 
-    // Extract metadata
-    let mut metadata = extract_metadata(&file_path)?;
-    metadata.total_nulls = calculate_total_nulls(&df);
+    let offsets = Arc::new(row_group_offsets(file_path)?);
+    state
+        .cache
+        .lock()
+        .unwrap()
+        .insert_row_group_offsets(file_path, offsets.clone());
+    Ok(offsets)
+}
+
+// The query that returns the result of a SQL statement, with its parameters bound. Session
+// statements return the rows of the view or table they create, or nothing when they drop one.
+fn result_sql(
+    query: &str,
+    parameters: &Option<HashMap<String, SqlParameter>>,
+) -> Result<Option<String>, String> {
+    let bound = bind_parameters(query, parameters)?;
+    Ok(match parse_session_statement(&bound) {
+        Some(statement) => statement.result_query(),
+        None => Some(bound),
+    })
+}
+
+// Run a SQL query and collect its result. Without ORDER BY, a query may return its rows in
+// another order each time it runs, so the result is collected once instead of paged. Results
+// larger than the cache budget are refused.
+fn load_query_view(
+    state: &AppState,
+    window: &Window,
+    job: &Job,
+    sql: &str,
+    key: &CacheKey,
+    all_files: &[String],
+) -> Result<CachedData, String> {
+    let (mut ctx, broken) =
+        build_session_context(state, window, all_files, &key.table_names, &key.csv_options)?;
+    let qlf = execute_session_query(&mut ctx, &broken, sql)?;
+
+    // The in-memory engine is interrupted when the job is cancelled
+    let df = job.collect(qlf)?;
+
+    let budget_bytes = state.cache.lock().unwrap().budget_bytes();
+    let size = df.estimated_size();
+    if size > budget_bytes {
+        return Err(format!(
+            "Query result needs {} MB, more than the cache budget of {} MB; add a LIMIT",
+            size.div_ceil(1024 * 1024),
+            budget_bytes / (1024 * 1024)
+        ));
+    }
+
+    Ok(CachedData::Collected(df))
+}
+
+// Build the response for a view from one page of its rows
+fn view_info(
+    job: &Job,
+    data: &CachedData,
+    metadata_path: &str,
+    offset: usize,
+    limit: usize,
+) -> Result<DataFrameInfo, String> {
+    let page = data.page(job, offset, limit)?;
+
+    // Keep file metadata so footer still shows file info
+    let mut metadata = extract_metadata(metadata_path)?;
+    metadata.total_nulls = data.total_nulls();
 
     Ok(DataFrameInfo {
-        shape,
-        columns,
-        rows,
+        shape: (data.height(), page.width()),
+        columns: extract_columns(&page),
+        rows: dataframe_to_rows(&page)?,
         metadata: Some(metadata),
     })
 }
 
+// Get initial data with metadata (returns first 100 rows). A row group limits the view to
+// the rows of one Parquet row group.
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
+fn get_data(
+    state: tauri::State<AppState>,
+    app_handle: AppHandle,
+    file_path: String,
+    sorting: Option<Vec<Sorting>>,
+    csv_options: Option<CsvOptions>,
+    filters: Option<Vec<Filter>>,
+    row_group: Option<usize>,
+    job_id: Option<String>,
+) -> Result<DataFrameInfo, String> {
+    state.jobs.run(&app_handle, "get_data", job_id, |job| {
+        let key = CacheKey {
+            file_path: Some(file_path.clone()),
+            filters,
+            sorting,
+            csv_options: csv_options_by_path(&file_path, csv_options),
+            row_group,
+            ..Default::default()
+        };
+
+        // Check cache
+        let cached = state.cache.lock().unwrap().get(&key);
+        if let Some(data) = cached {
+            return view_info(job, &data, &file_path, 0, 100);
+        }
+
+        let data = load_file_view(
+            &state,
+            job,
+            &file_path,
+            &key.filters,
+            &key.sorting,
+            &key.csv_options,
+            key.row_group,
+        )?;
+
+        // Update cache, unless the job was cancelled
+        job.check_cancelled()?;
+        state
+            .cache
+            .lock()
+            .unwrap()
+            .insert(key, vec![file_path.clone()], data.clone());
+
+        view_info(job, &data, &file_path, 0, 100)
+    })
+}
+
 // Get more rows with pagination (offset and limit)
-#[tauri::command]
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
 fn get_more_rows(
     state: tauri::State<AppState>,
+    app_handle: AppHandle,
     file_path: String,
     offset: usize,
     limit: usize,
     sorting: Option<Vec<Sorting>>,
+    csv_options: Option<CsvOptions>,
+    filters: Option<Vec<Filter>>,
+    row_group: Option<usize>,
+    job_id: Option<String>,
 ) -> Result<Vec<Vec<String>>, String> {
-    // Check cache
-    {
-        let cache = state.cache.lock().unwrap();
-        if let Some(entry) = cache.as_ref() {
-            if entry.file_path.as_deref() == Some(&file_path)
-                && entry.sorting == sorting
-                && entry.query.is_none()
-            {
-                let df = &entry.df;
-                let df_slice = df.slice(offset as i64, limit);
-                return dataframe_to_rows(&df_slice);
-            }
-        }
-    }
-
-    // Open file
-    let mut lf = if file_path.to_lowercase().ends_with(".csv") {
-        open_csv(&file_path)?
-    } else {
-        open_parquet(&file_path)?
-    };
-
-    // Apply sorts if provided
-    if let Some(sorts) = sorting {
-        lf = apply_sorts(lf, sorts)?;
-    }
-
-    // Apply pagination
-    let lf = lf.slice(offset as i64, limit as u32);
+    state.jobs.run(&app_handle, "get_more_rows", job_id, |job| {
+        let key = CacheKey {
+            file_path: Some(file_path.clone()),
+            filters,
+            sorting,
+            csv_options: csv_options_by_path(&file_path, csv_options),
+            row_group,
+            ..Default::default()
+        };
 
-    // Collect DataFrame
-    let df = lf
-        .collect()
-        .map_err(|e| format!("Failed to collect DataFrame: {}", e))?;
+        // Check cache
+        let cached = state.cache.lock().unwrap().get(&key);
+        if let Some(data) = cached {
+            return dataframe_to_rows(&data.page(job, offset, limit)?);
+        }
 
-    // Convert to rows
-    dataframe_to_rows(&df)
+        // Load the view again, so a sorted view is not sorted again for every page
+        let data = load_file_view(
+            &state,
+            job,
+            &file_path,
+            &key.filters,
+            &key.sorting,
+            &key.csv_options,
+            key.row_group,
+        )?;
+
+        job.check_cancelled()?;
+        state
+            .cache
+            .lock()
+            .unwrap()
+            .insert(key, vec![file_path.clone()], data.clone());
+
+        dataframe_to_rows(&data.page(job, offset, limit)?)
+    })
 }
 
 // Execute SQL and return preview (paginated first page) replacing current table
-#[tauri::command]
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
 fn execute_sql(
     state: tauri::State<AppState>,
+    app_handle: AppHandle,
+    window: Window,
     active_file_path: String,
     all_files: Vec<String>,
     table_names: Option<HashMap<String, String>>,
+    csv_options: Option<HashMap<String, CsvOptions>>,
     query: String,
+    parameters: Option<HashMap<String, SqlParameter>>,
     offset: Option<usize>,
     limit: Option<usize>,
+    job_id: Option<String>,
 ) -> Result<DataFrameInfo, String> {
     let offset = offset.unwrap_or(0);
     let limit = limit.unwrap_or(100);
 
-    // Check cache
-    {
-        let cache = state.cache.lock().unwrap();
-        if let Some(entry) = cache.as_ref() {
-            if entry.query.as_ref() == Some(&query) {
-                let df = &entry.df;
-                let shape = df.shape();
-                let columns = extract_columns(df);
-
-                let df_page = df.slice(offset as i64, limit);
-                let rows = dataframe_to_rows(&df_page)?;
-                let mut metadata = extract_metadata(&active_file_path)?;
-                metadata.total_nulls = calculate_total_nulls(df);
-
-                return Ok(DataFrameInfo {
-                    shape,
-                    columns,
-                    rows,
-                    metadata: Some(metadata),
-                });
-            }
-        }
-    }
-
-    let mut ctx = SQLContext::new();
-
-    // Register all open files as tables
-    for path in &all_files {
-        let lf = if path.to_lowercase().ends_with(".csv") {
-            open_csv(path)?
-        } else {
-            open_parquet(path)?
+    state.jobs.run(&app_handle, "execute_sql", job_id, |job| {
+        let key = CacheKey {
+            query: Some(query.clone()),
+            table_names,
+            csv_options,
+            session: Some(window.label().to_string()),
+            parameters,
+            ..Default::default()
         };
-        let table_name = if let Some(ref names) = table_names {
-            names
-                .get(path)
-                .cloned()
-                .unwrap_or_else(|| table_name_from_path(path))
-        } else {
-            table_name_from_path(path)
+        let bound = bind_parameters(&query, &key.parameters)?;
+
+        // CREATE VIEW and CREATE TABLE statements show the rows of what they created
+        let sql = match parse_session_statement(&bound) {
+            Some(statement) => {
+                run_session_statement(
+                    &state,
+                    &window,
+                    job,
+                    &statement,
+                    &all_files,
+                    &key.table_names,
+                    &key.csv_options,
+                )?;
+                match statement.result_query() {
+                    Some(sql) => sql,
+                    None => {
+                        let empty = CachedData::Collected(DataFrame::empty());
+                        return view_info(job, &empty, &active_file_path, 0, 0);
+                    }
+                }
+            }
+            None => bound,
         };
-        ctx.register(&table_name, lf);
-    }
-
-    // Build main query lazyframe
-    let qlf = execute_sql_query(&mut ctx, &query)?;
-
-    // Collect full DataFrame for the query result to allow efficient pagination/sorting
-    let df = qlf
-        .collect()
-        .map_err(|e| format!("Failed to collect SQL result: {}", e))?;
-
-    // Update cache
-    {
-        let mut cache = state.cache.lock().unwrap();
-        *cache = Some(CacheEntry {
-            file_path: None,
-            sorting: None,
-            query: Some(query.clone()),
-            df: df.clone(),
-        });
-    }
 
-    let shape = df.shape();
-
-    // Get column information
-    let columns = extract_columns(&df);
+        // Check cache
+        let cached = state.cache.lock().unwrap().get(&key);
+        if let Some(data) = cached {
+            return view_info(job, &data, &active_file_path, offset, limit);
+        }
 
-    let df_page = df.slice(offset as i64, limit);
-    let rows = dataframe_to_rows(&df_page)?;
+        let data = load_query_view(&state, &window, job, &sql, &key, &all_files)?;
 
-    // Keep file metadata so footer still shows file info
-    let mut metadata = extract_metadata(&active_file_path)?;
-    metadata.total_nulls = calculate_total_nulls(&df);
+        // Update cache, unless the job was cancelled
+        job.check_cancelled()?;
+        state
+            .cache
+            .lock()
+            .unwrap()
+            .insert(key, all_files, data.clone());
 
-    Ok(DataFrameInfo {
-        shape,
-        columns,
-        rows,
-        metadata: Some(metadata),
+        view_info(job, &data, &active_file_path, offset, limit)
     })
 }
 
 // Fetch more rows for the same SQL query (infinite scroll)
-#[tauri::command]
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
 fn get_more_sql_rows(
     state: tauri::State<AppState>,
+    app_handle: AppHandle,
+    window: Window,
     all_files: Vec<String>,
     table_names: Option<HashMap<String, String>>,
+    csv_options: Option<HashMap<String, CsvOptions>>,
     query: String,
+    parameters: Option<HashMap<String, SqlParameter>>,
     offset: usize,
     limit: usize,
+    job_id: Option<String>,
 ) -> Result<Vec<Vec<String>>, String> {
-    // Check cache
-    {
-        let cache = state.cache.lock().unwrap();
-        if let Some(entry) = cache.as_ref() {
-            if entry.query.as_ref() == Some(&query) {
-                let df = &entry.df;
-                let df_slice = df.slice(offset as i64, limit);
-                return dataframe_to_rows(&df_slice);
+    state
+        .jobs
+        .run(&app_handle, "get_more_sql_rows", job_id, |job| {
+            let key = CacheKey {
+                query: Some(query.clone()),
+                table_names,
+                csv_options,
+                session: Some(window.label().to_string()),
+                parameters,
+                ..Default::default()
+            };
+
+            // Check cache
+            let cached = state.cache.lock().unwrap().get(&key);
+            if let Some(data) = cached {
+                return dataframe_to_rows(&data.page(job, offset, limit)?);
             }
-        }
-    }
 
-    let mut ctx = SQLContext::new();
+            // Session statements already ran; page through what they created
+            let Some(sql) = result_sql(&query, &key.parameters)? else {
+                return Ok(Vec::new());
+            };
 
-    // Register all open files as tables
-    for path in &all_files {
-        let lf = open_parquet(path)?;
-        let table_name = if let Some(ref names) = table_names {
-            names
-                .get(path)
-                .cloned()
-                .unwrap_or_else(|| table_name_from_path(path))
-        } else {
-            table_name_from_path(path)
-        };
-        ctx.register(&table_name, lf);
-    }
+            // Pages of a query without ORDER BY may come back in any order, so the result is
+            // collected once and paged from the cache
+            let data = load_query_view(&state, &window, job, &sql, &key, &all_files)?;
 
-    let qlf = execute_sql_query(&mut ctx, &query)?;
+            job.check_cancelled()?;
+            state
+                .cache
+                .lock()
+                .unwrap()
+                .insert(key, all_files, data.clone());
 
-    let page_df = qlf
-        .slice(offset as i64, limit as u32)
-        .collect()
-        .map_err(|e| format!("Failed to collect SQL page: {}", e))?;
+            dataframe_to_rows(&data.page(job, offset, limit)?)
+        })
+}
 
-    dataframe_to_rows(&page_df)
+// Logical plan of a SQL query before and after optimization, built with the same tables as
+// execute_sql. The optimized plan shows the projections and predicates pushed into the scans.
+#[tauri::command(async)]
+fn explain_sql(
+    state: tauri::State<AppState>,
+    window: Window,
+    all_files: Vec<String>,
+    table_names: Option<HashMap<String, String>>,
+    csv_options: Option<HashMap<String, CsvOptions>>,
+    query: String,
+    parameters: Option<HashMap<String, SqlParameter>>,
+) -> Result<QueryPlan, String> {
+    let query = bind_parameters(&query, &parameters)?;
+
+    // A CREATE statement is explained by the query it would store
+    let query = match parse_session_statement(&query) {
+        Some(SessionStatement::Create { query, .. }) => query,
+        Some(SessionStatement::Drop { .. }) => {
+            return Err("DROP statements have no query plan".to_string())
+        }
+        None => query,
+    };
+
+    let (mut ctx, broken) =
+        build_session_context(&state, &window, &all_files, &table_names, &csv_options)?;
+    let qlf = execute_session_query(&mut ctx, &broken, &query)?;
+    explain_query(&qlf)
+}
+
+// Views and temporary tables of the window's SQL session, in the order they were created
+#[tauri::command]
+fn list_sql_tables(state: tauri::State<AppState>, window: Window) -> Vec<SessionTableInfo> {
+    state
+        .sql_sessions
+        .lock()
+        .unwrap()
+        .get(window.label())
+        .map(|session| session.list())
+        .unwrap_or_default()
 }
 
-// Get statistics for all columns
+// Remove a view or temporary table from the window's SQL session
 #[tauri::command]
+fn drop_sql_table(
+    state: tauri::State<AppState>,
+    window: Window,
+    name: String,
+) -> Result<(), String> {
+    let dropped = state
+        .sql_sessions
+        .lock()
+        .unwrap()
+        .get_mut(window.label())
+        .is_some_and(|session| session.drop_table(&name));
+    if !dropped {
+        return Err(format!("Table not found: {}", name));
+    }
+
+    state.cache.lock().unwrap().evict_queries();
+    Ok(())
+}
+
+// Get statistics for the given columns, or all columns, of a file view. The file is never fully
+// collected: Parquet footers provide min/max and null counts, and the rest is aggregated per column.
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
 fn get_statistics(
+    state: tauri::State<AppState>,
+    app_handle: AppHandle,
     file_path: String,
+    csv_options: Option<CsvOptions>,
+    sorting: Option<Vec<Sorting>>,
+    filters: Option<Vec<Filter>>,
+    columns: Option<Vec<String>>,
+    job_id: Option<String>,
 ) -> Result<HashMap<String, HashMap<String, serde_json::Value>>, String> {
-    // Open Parquet file
-    let lf = open_parquet(&file_path)?;
+    state
+        .jobs
+        .run(&app_handle, "get_statistics", job_id, |job| {
+            let key = CacheKey {
+                file_path: Some(file_path.clone()),
+                filters,
+                sorting,
+                csv_options: csv_options_by_path(&file_path, csv_options),
+                ..Default::default()
+            };
+            let lf = file_view(&state, &file_path, &key)?;
+            let known = view_footer_statistics(&file_path, &lf, key.filters.as_deref())?;
+
+            calculate_statistics_lazy(&job.cancellable(lf), columns.as_deref(), known, |lf| {
+                job.collect_streaming(lf)
+            })
+        })
+}
 
-    // Collect DataFrame
-    let df = lf
-        .collect()
-        .map_err(|e| format!("Failed to collect DataFrame: {}", e))?;
+// A file view as a LazyFrame: the cached view if it is loaded, otherwise the file with the
+// filters applied. Row order doesn't change statistics, so views that aren't cached are not sorted.
+fn file_view(state: &AppState, file_path: &str, key: &CacheKey) -> Result<LazyFrame, String> {
+    if let Some(data) = state.cache.lock().unwrap().get(key) {
+        return Ok(data.lazy());
+    }
+
+    let mut lf = open_file(file_path, &read_options_for(file_path, &key.csv_options))?;
+    if let Some(filters) = &key.filters {
+        lf = apply_filters(lf, filters.clone())?;
+    }
 
-    // Calculate statistics
-    calculate_statistics(&df)
+    Ok(lf)
 }
 
-#[tauri::command]
+// A view as a LazyFrame for statistics: the cached view, otherwise the file with its filters
+// applied or the SQL result. File views are not sorted, as in file_view.
+fn view_lazy(
+    state: &AppState,
+    window: &Window,
+    job: &Job,
+    view: ViewRef,
+) -> Result<LazyFrame, String> {
+    match view {
+        ViewRef::File {
+            file_path,
+            csv_options,
+            filters,
+            sorting,
+            row_group: None,
+        } => {
+            let key = CacheKey {
+                file_path: Some(file_path.clone()),
+                filters,
+                sorting,
+                csv_options: csv_options_by_path(&file_path, csv_options),
+                ..Default::default()
+            };
+            file_view(state, &file_path, &key)
+        }
+        view => Ok(view_data(state, window, job, view)?.lazy()),
+    }
+}
+
+// Data of a view named by the frontend: the cached view, or the view loaded again if it was
+// evicted. Views loaded again are not cached.
+fn view_data(
+    state: &AppState,
+    window: &Window,
+    job: &Job,
+    view: ViewRef,
+) -> Result<CachedData, String> {
+    match view {
+        ViewRef::File {
+            file_path,
+            csv_options,
+            filters,
+            sorting,
+            row_group,
+        } => {
+            let key = CacheKey {
+                file_path: Some(file_path.clone()),
+                filters,
+                sorting,
+                csv_options: csv_options_by_path(&file_path, csv_options),
+                row_group,
+                ..Default::default()
+            };
+            if let Some(data) = state.cache.lock().unwrap().get(&key) {
+                return Ok(data);
+            }
+            load_file_view(
+                state,
+                job,
+                &file_path,
+                &key.filters,
+                &key.sorting,
+                &key.csv_options,
+                key.row_group,
+            )
+        }
+        ViewRef::Query {
+            query,
+            all_files,
+            table_names,
+            csv_options,
+            parameters,
+        } => {
+            let key = CacheKey {
+                query: Some(query.clone()),
+                table_names,
+                csv_options,
+                session: Some(window.label().to_string()),
+                parameters,
+                ..Default::default()
+            };
+            if let Some(data) = state.cache.lock().unwrap().get(&key) {
+                return Ok(data);
+            }
+            match result_sql(&query, &key.parameters)? {
+                Some(sql) => load_query_view(state, window, job, &sql, &key, &all_files),
+                None => Ok(CachedData::Collected(DataFrame::empty())),
+            }
+        }
+    }
+}
+
+#[tauri::command(async)]
 fn get_query_statistics(
     state: tauri::State<AppState>,
-    query: String,
+    app_handle: AppHandle,
+    window: Window,
+    view: ViewRef,
+    columns: Option<Vec<String>>,
+    job_id: Option<String>,
 ) -> Result<HashMap<String, HashMap<String, serde_json::Value>>, String> {
-    // Get the cached query result
-    let cache = state.cache.lock().unwrap();
-    if let Some(entry) = cache.as_ref() {
-        if entry.query.as_ref() == Some(&query) {
-            let df = &entry.df;
-            return calculate_statistics(df);
-        }
-    }
-    Err("Query result not found in cache".to_string())
+    state
+        .jobs
+        .run(&app_handle, "get_query_statistics", job_id, |job| {
+            let data = view_data(&state, &window, job, view)?;
+            calculate_statistics_lazy(&data.lazy(), columns.as_deref(), HashMap::new(), |lf| {
+                job.collect(lf)
+            })
+        })
+}
+
+// Write a profile report of a file view or of a SQL query result to an HTML or
+// JSON file, depending on the extension of output_path
+#[tauri::command(async)]
+fn profile_dataset(
+    state: tauri::State<AppState>,
+    app_handle: AppHandle,
+    window: Window,
+    view: ViewRef,
+    output_path: String,
+    job_id: Option<String>,
+) -> Result<(), String> {
+    state
+        .jobs
+        .run(&app_handle, "profile_dataset", job_id, |job| {
+            let source = match &view {
+                ViewRef::File { file_path, .. } => file_path.clone(),
+                ViewRef::Query { query, .. } => query.clone(),
+            };
+            let lf = view_lazy(&state, &window, job, view)?;
+
+            // Each query of the profile holds at most the cache budget, like a SQL result
+            let budget_bytes = state.cache.lock().unwrap().budget_bytes();
+            let report = profile_lazy(&job.cancellable(lf), &source, |lf| {
+                let df = job.collect_streaming(lf)?;
+                let size = df.estimated_size();
+                if size > budget_bytes {
+                    return Err(format!(
+                        "Profile needs {} MB, more than the cache budget of {} MB; filter the view",
+                        size.div_ceil(1024 * 1024),
+                        budget_bytes / (1024 * 1024)
+                    ));
+                }
+                Ok(df)
+            })?;
+            job.check_cancelled()?;
+
+            write_report(&report, &output_path)
+        })
 }
 
-// Extract file metadata
+// Extract file metadata. Parquet directories and globs are summed across all files.
 fn extract_metadata(file_path: &str) -> Result<MetadataInfo, String> {
-    let file_metadata =
-        fs::metadata(file_path).map_err(|e| format!("Failed to read file metadata: {}", e))?;
+    let is_parquet = resolve_format(file_path)?.kind == FormatKind::Parquet;
+
+    let files = if is_parquet {
+        dataset_files(file_path)?
+    } else {
+        vec![PathBuf::from(file_path)]
+    };
 
     let name = std::path::Path::new(file_path)
         .file_name()
@@ -426,83 +995,101 @@ fn extract_metadata(file_path: &str) -> Result<MetadataInfo, String> {
         .unwrap_or("")
         .to_string();
 
-    let size = file_metadata.len();
+    let mut size = 0;
+    let mut created: Option<std::time::SystemTime> = None;
+    let mut modified: Option<std::time::SystemTime> = None;
 
-    let created = file_metadata.created().ok().and_then(|t| {
-        chrono::DateTime::<chrono::Local>::from(t)
-            .format("%Y-%m-%d %H:%M:%S")
-            .to_string()
-            .into()
-    });
+    for file in &files {
+        let file_metadata =
+            fs::metadata(file).map_err(|e| format!("Failed to read file metadata: {}", e))?;
+
+        size += file_metadata.len();
 
-    let modified = file_metadata.modified().ok().and_then(|t| {
+        // Earliest creation and latest modification across the dataset
+        if let Ok(t) = file_metadata.created() {
+            created = Some(created.map_or(t, |c| c.min(t)));
+        }
+        if let Ok(t) = file_metadata.modified() {
+            modified = Some(modified.map_or(t, |m| m.max(t)));
+        }
+    }
+
+    let format_time = |t: std::time::SystemTime| {
         chrono::DateTime::<chrono::Local>::from(t)
             .format("%Y-%m-%d %H:%M:%S")
             .to_string()
-            .into()
-    });
+    };
+    let created = created.map(format_time);
+    let modified = modified.map(format_time);
 
-    // Handle CSV files differently as they don't have Parquet metadata
-    if file_path.to_lowercase().ends_with(".csv") {
+    // Only Parquet files carry row group and compression metadata
+    if !is_parquet {
         return Ok(MetadataInfo {
             name,
             created,
             modified,
             size,
+            file_count: files.len(),
             row_groups: 0,
             compression: "NONE".to_string(),
             total_nulls: 0,
         });
     }
 
-    // Open the Parquet file to get row groups and compression info
-    let file = fs::File::open(file_path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut row_groups = 0;
+    let mut compression: Vec<String> = Vec::new();
 
-    let reader = parquet::file::serialized_reader::SerializedFileReader::new(file)
-        .map_err(|e| format!("Failed to create Parquet reader: {}", e))?;
+    // Open each Parquet file to get row groups and compression info
+    for file in &files {
+        let file = fs::File::open(file).map_err(|e| format!("Failed to open file: {}", e))?;
 
-    let metadata = reader.metadata();
-    let row_groups = metadata.num_row_groups();
+        let reader = parquet::file::serialized_reader::SerializedFileReader::new(file)
+            .map_err(|e| format!("Failed to create Parquet reader: {}", e))?;
 
-    let compression = if row_groups > 0 {
-        let rg_metadata = metadata.row_group(0);
-        if rg_metadata.num_columns() > 0 {
-            format!("{:?}", rg_metadata.column(0).compression())
-        } else {
-            "UNCOMPRESSED".to_string()
+        let metadata = reader.metadata();
+        row_groups += metadata.num_row_groups();
+
+        // Every codec used by a column chunk, as columns can be compressed differently
+        for rg_metadata in metadata.row_groups() {
+            for column in rg_metadata.columns() {
+                let codec = format!("{:?}", column.compression());
+                if !compression.contains(&codec) {
+                    compression.push(codec);
+                }
+            }
         }
-    } else {
-        "UNCOMPRESSED".to_string()
-    };
+    }
 
     Ok(MetadataInfo {
         name,
         created,
         modified,
         size,
+        file_count: files.len(),
         row_groups,
-        compression,
+        compression: if compression.is_empty() {
+            "UNCOMPRESSED".to_string()
+        } else {
+            compression.join(", ")
+        },
         total_nulls: 0, // Will be updated when we have DataFrame
     })
 }
 
-// Calculate total null count from DataFrame
-fn calculate_total_nulls(df: &DataFrame) -> usize {
-    df.get_columns()
-        .iter()
-        .map(|series| series.null_count())
-        .sum()
-}
-
 // Save DataFrame to Parquet file
-#[tauri::command]
-fn save_parquet(state: tauri::State<AppState>, file_path: String) -> Result<(), String> {
-    // Get the cached DataFrame
-    let cache = state.cache.lock().unwrap();
-    let df = cache
-        .as_ref()
-        .map(|entry| &entry.df)
-        .ok_or_else(|| "No data to save".to_string())?;
+#[tauri::command(async)]
+fn save_parquet(
+    state: tauri::State<AppState>,
+    app_handle: AppHandle,
+    window: Window,
+    file_path: String,
+    view: ViewRef,
+    job_id: Option<String>,
+) -> Result<(), String> {
+    // Get the DataFrame of the view
+    let mut df = state.jobs.run(&app_handle, "save_parquet", job_id, |job| {
+        view_data(&state, &window, job, view)?.to_dataframe(job)
+    })?;
 
     // Create parent directories if they don't exist
     if let Some(parent) = std::path::Path::new(&file_path).parent() {
@@ -514,21 +1101,26 @@ fn save_parquet(state: tauri::State<AppState>, file_path: String) -> Result<(),
 
     // Write DataFrame to Parquet
     polars::prelude::ParquetWriter::new(file)
-        .finish(&mut df.clone())
+        .finish(&mut df)
         .map_err(|e| format!("Failed to write Parquet file: {}", e))?;
 
     Ok(())
 }
 
 // Save DataFrame to CSV file
-#[tauri::command]
-fn save_csv(state: tauri::State<AppState>, file_path: String) -> Result<(), String> {
-    // Get the cached DataFrame
-    let cache = state.cache.lock().unwrap();
-    let df = cache
-        .as_ref()
-        .map(|entry| &entry.df)
-        .ok_or_else(|| "No data to save".to_string())?;
+#[tauri::command(async)]
+fn save_csv(
+    state: tauri::State<AppState>,
+    app_handle: AppHandle,
+    window: Window,
+    file_path: String,
+    view: ViewRef,
+    job_id: Option<String>,
+) -> Result<(), String> {
+    // Get the DataFrame of the view
+    let mut df = state.jobs.run(&app_handle, "save_csv", job_id, |job| {
+        view_data(&state, &window, job, view)?.to_dataframe(job)
+    })?;
 
     // Create parent directories if they don't exist
     if let Some(parent) = std::path::Path::new(&file_path).parent() {
@@ -541,16 +1133,20 @@ fn save_csv(state: tauri::State<AppState>, file_path: String) -> Result<(), Stri
     // Write DataFrame to CSV
     polars::prelude::CsvWriter::new(file)
         .include_header(true)
-        .finish(&mut df.clone())
+        .finish(&mut df)
         .map_err(|e| format!("Failed to write CSV file: {}", e))?;
 
     Ok(())
 }
 
 // Legacy command for backward compatibility
-#[tauri::command]
-fn read_parquet(state: tauri::State<AppState>, file_path: String) -> Result<DataFrameInfo, String> {
-    get_data(state, file_path, None)
+#[tauri::command(async)]
+fn read_parquet(
+    state: tauri::State<AppState>,
+    app_handle: AppHandle,
+    file_path: String,
+) -> Result<DataFrameInfo, String> {
+    get_data(state, app_handle, file_path, None, None, None, None, None)
 }
 
 // Settings structure
@@ -561,6 +1157,8 @@ struct Settings {
     font_family: String,
     #[serde(default = "default_font_size", rename = "fontSize")]
     font_size: u32,
+    #[serde(default = "default_cache_memory_mb", rename = "cacheMemoryMb")]
+    cache_memory_mb: usize,
 }
 
 fn default_font_family() -> String {
@@ -571,12 +1169,17 @@ fn default_font_size() -> u32 {
     14
 }
 
+fn default_cache_memory_mb() -> usize {
+    DEFAULT_CACHE_BUDGET_MB
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Settings {
             theme: "dark".to_string(),
             font_family: default_font_family(),
             font_size: default_font_size(),
+            cache_memory_mb: default_cache_memory_mb(),
         }
     }
 }
@@ -595,9 +1198,67 @@ fn get_settings_path(app_handle: tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(app_data_dir.join("settings.json"))
 }
 
+// Get the path of the saved per-file CSV options in app data directory
+fn get_csv_options_path(app_handle: tauri::AppHandle) -> Result<PathBuf, String> {
+    let settings_path = get_settings_path(app_handle)?;
+    Ok(settings_path.with_file_name("csv_options.json"))
+}
+
+fn read_saved_csv_options(
+    app_handle: tauri::AppHandle,
+) -> Result<HashMap<String, CsvOptions>, String> {
+    let options_path = get_csv_options_path(app_handle)?;
+
+    if !options_path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let contents = fs::read_to_string(&options_path)
+        .map_err(|e| format!("Failed to read CSV options file: {}", e))?;
+
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse CSV options: {}", e))
+}
+
+// Load the CSV options saved for a file
+#[tauri::command]
+fn load_csv_options(
+    app_handle: tauri::AppHandle,
+    file_path: String,
+) -> Result<Option<CsvOptions>, String> {
+    let mut saved = read_saved_csv_options(app_handle)?;
+    Ok(saved.remove(&file_path))
+}
+
+// Save the CSV options for a file, or forget them when `options` is None
+#[tauri::command]
+fn save_csv_options(
+    app_handle: tauri::AppHandle,
+    file_path: String,
+    options: Option<CsvOptions>,
+) -> Result<(), String> {
+    let mut saved = read_saved_csv_options(app_handle.clone())?;
+
+    match options {
+        Some(options) => saved.insert(file_path, options),
+        None => saved.remove(&file_path),
+    };
+
+    let options_path = get_csv_options_path(app_handle)?;
+    let json = serde_json::to_string_pretty(&saved)
+        .map_err(|e| format!("Failed to serialize CSV options: {}", e))?;
+
+    fs::write(&options_path, json)
+        .map_err(|e| format!("Failed to write CSV options file: {}", e))?;
+
+    Ok(())
+}
+
 // Load settings from file
 #[tauri::command]
-fn load_settings(app_handle: tauri::AppHandle) -> Result<Settings, String> {
+fn load_settings(
+    state: tauri::State<AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Settings, String> {
     let settings_path = get_settings_path(app_handle)?;
 
     if !settings_path.exists() {
@@ -611,14 +1272,22 @@ fn load_settings(app_handle: tauri::AppHandle) -> Result<Settings, String> {
     let settings: Settings =
         serde_json::from_str(&contents).map_err(|e| format!("Failed to parse settings: {}", e))?;
 
+    apply_cache_budget(&state, &settings);
+
     Ok(settings)
 }
 
 // Save settings to file
 #[tauri::command]
-fn save_settings(app_handle: tauri::AppHandle, settings: Settings) -> Result<(), String> {
+fn save_settings(
+    state: tauri::State<AppState>,
+    app_handle: tauri::AppHandle,
+    settings: Settings,
+) -> Result<(), String> {
     let settings_path = get_settings_path(app_handle)?;
 
+    apply_cache_budget(&state, &settings);
+
     let json = serde_json::to_string_pretty(&settings)
         .map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
@@ -627,156 +1296,325 @@ fn save_settings(app_handle: tauri::AppHandle, settings: Settings) -> Result<(),
     Ok(())
 }
 
-#[derive(Serialize)]
-struct HistogramData {
-    bins: Vec<f64>,
-    counts: Vec<usize>,
-    min: f64,
-    max: f64,
+// Resize the view cache to the memory budget from settings
+fn apply_cache_budget(state: &AppState, settings: &Settings) {
+    let budget_bytes = settings.cache_memory_mb.saturating_mul(1024 * 1024);
+    state.cache.lock().unwrap().set_budget(budget_bytes);
+}
+
+// Schema of a file as a tree of nested fields. Parquet files add physical and logical types,
+// repetition, field IDs and decimal precision and scale.
+#[tauri::command(async)]
+fn get_schema_tree(
+    file_path: String,
+    csv_options: Option<CsvOptions>,
+) -> Result<Vec<SchemaNode>, String> {
+    let mut lf = open_file(&file_path, &ReadOptions { csv: csv_options })?;
+    let schema = lf
+        .collect_schema()
+        .map_err(|e| format!("Failed to read schema: {}", e))?;
+    schema_tree(&file_path, &schema)
+}
+
+// Full footer of every file of a Parquet path: row groups, column chunks, statistics and the
+// key-value metadata written with it
+#[tauri::command(async)]
+fn get_parquet_metadata(file_path: String) -> Result<Vec<ParquetFooter>, String> {
+    parquet_footers(&file_path)
+}
+
+// Pages of one column chunk of a Parquet file: page types, encodings and sizes, and whether
+// page indexes and bloom filters were written
+#[tauri::command(async)]
+fn get_column_pages(
+    file_path: String,
+    row_group: usize,
+    column: String,
+) -> Result<ColumnPages, String> {
+    column_pages(&file_path, row_group, &column)
+}
+
+// Row groups of a Parquet path with their row ranges and column statistics
+#[tauri::command(async)]
+fn get_row_groups(file_path: String) -> Result<Vec<RowGroupRange>, String> {
+    row_groups(&file_path)
+}
+
+// Row group a displayed row of a Parquet view came from. Filtered and sorted views keep the
+// row number in the file of each row; otherwise it follows from the position.
+#[tauri::command(async)]
+fn locate_row(
+    state: tauri::State<AppState>,
+    app_handle: AppHandle,
+    window: Window,
+    view: ViewRef,
+    row: usize,
+    job_id: Option<String>,
+) -> Result<RowLocation, String> {
+    state.jobs.run(&app_handle, "locate_row", job_id, |job| {
+        let ViewRef::File {
+            file_path,
+            filters,
+            sorting,
+            row_group,
+            ..
+        } = &view
+        else {
+            return Err("Rows of SQL results have no row group".to_string());
+        };
+        let offsets = cached_row_group_offsets(&state, file_path)?;
+
+        let filtered = filters.as_ref().is_some_and(|filters| !filters.is_empty());
+        let sorted = sorting.as_ref().is_some_and(|sorts| !sorts.is_empty());
+        if !filtered && !sorted {
+            let first_row = match row_group {
+                Some(row_group) => row_group_range(&offsets, *row_group)?.0,
+                None => 0,
+            };
+            return locate_row_group(&offsets, first_row + row);
+        }
+
+        let data = view_data(&state, &window, job, view.clone())?;
+        locate_row_group(&offsets, data.file_row(job, row)?)
+    })
+}
+
+// Row groups of a Parquet path that filters would skip, judged by the min/max and null count
+// statistics in the footers, with the rows and bytes skipped and read
+#[tauri::command(async)]
+fn explain_pruning(file_path: String, filters: Vec<Filter>) -> Result<PruningReport, String> {
+    let mut lf = open_file(&file_path, &ReadOptions::default())?;
+    let schema = lf
+        .collect_schema()
+        .map_err(|e| format!("Failed to read schema: {}", e))?;
+    row_group_pruning(&file_path, &schema, &filters)
+}
+
+// Cancel a running job. Returns false if the job has already finished.
+#[tauri::command]
+fn cancel_job(state: tauri::State<AppState>, job_id: String) -> bool {
+    state.jobs.cancel(&job_id)
 }
 
+// Memory used by cached views
 #[tauri::command]
+fn get_cache_usage(state: tauri::State<AppState>) -> CacheUsage {
+    state.cache.lock().unwrap().usage()
+}
+
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
 fn get_column_histogram(
+    state: tauri::State<AppState>,
+    app_handle: AppHandle,
     file_path: String,
     column_name: String,
     num_bins: Option<usize>,
+    options: Option<HistogramOptions>,
+    csv_options: Option<CsvOptions>,
+    sorting: Option<Vec<Sorting>>,
+    filters: Option<Vec<Filter>>,
+    job_id: Option<String>,
 ) -> Result<HistogramData, String> {
-    // Open the file fresh to ensure we're working with the correct data
-    let lf = open_parquet(&file_path)?;
-    let df = lf
-        .collect()
-        .map_err(|e| format!("Failed to collect DataFrame: {}", e))?;
-
-    calculate_histogram_from_dataframe(&df, &column_name, num_bins)
+    state
+        .jobs
+        .run(&app_handle, "get_column_histogram", job_id, |job| {
+            let key = CacheKey {
+                file_path: Some(file_path.clone()),
+                filters,
+                sorting,
+                csv_options: csv_options_by_path(&file_path, csv_options),
+                ..Default::default()
+            };
+            let lf = file_view(&state, &file_path, &key)?;
+
+            // Only the column is read
+            let df = job.collect(lf.select([col(column_name.as_str())]))?;
+            calculate_histogram_from_dataframe(
+                &df,
+                &column_name,
+                num_bins,
+                &options.unwrap_or_default(),
+            )
+        })
 }
 
-#[tauri::command]
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
 fn get_query_column_histogram(
     state: tauri::State<AppState>,
-    query: String,
+    app_handle: AppHandle,
+    window: Window,
+    view: ViewRef,
     column_name: String,
     num_bins: Option<usize>,
+    options: Option<HistogramOptions>,
+    job_id: Option<String>,
 ) -> Result<HistogramData, String> {
-    // Get the cached query result
-    let cache = state.cache.lock().unwrap();
-    if let Some(entry) = cache.as_ref() {
-        if entry.query.as_ref() == Some(&query) {
-            let df = &entry.df;
-            return calculate_histogram_from_dataframe(df, &column_name, num_bins);
-        }
-    }
-    Err("Query result not found in cache".to_string())
+    state
+        .jobs
+        .run(&app_handle, "get_query_column_histogram", job_id, |job| {
+            let data = view_data(&state, &window, job, view)?;
+            let df = job.collect(data.lazy().select([col(column_name.as_str())]))?;
+            calculate_histogram_from_dataframe(
+                &df,
+                &column_name,
+                num_bins,
+                &options.unwrap_or_default(),
+            )
+        })
 }
 
-fn calculate_histogram_from_dataframe(
-    df: &DataFrame,
-    column_name: &str,
-    num_bins: Option<usize>,
-) -> Result<HistogramData, String> {
-    // Get the column
-    let series = df
-        .column(&column_name)
-        .map_err(|e| format!("Column not found: {}", e))?;
-
-    // Convert to f64
-    let ca = series
-        .cast(&DataType::Float64)
-        .map_err(|e| format!("Failed to convert to numeric: {}", e))?;
-    let f64_series = ca
-        .f64()
-        .map_err(|e| format!("Failed to get f64 series: {}", e))?;
-
-    // Get min and max
-    let min = f64_series
-        .min()
-        .ok_or_else(|| "No min value in column".to_string())?;
-    let max = f64_series
-        .max()
-        .ok_or_else(|| "No max value in column".to_string())?;
-
-    // Calculate histogram
-    let bins_count = num_bins.unwrap_or(20);
-
-    // Handle edge case where all values are the same (min == max)
-    if (max - min).abs() < f64::EPSILON {
-        // All values are the same, create a single bin
-        let bins = vec![min, min];
-        let mut counts = vec![0usize; 1];
-
-        // Count all non-null values
-        for opt_val in f64_series.into_iter() {
-            if opt_val.is_some() {
-                counts[0] += 1;
-            }
-        }
+// Pearson and Spearman correlations between the numeric columns of a file view
+#[tauri::command(async)]
+fn get_correlations(
+    state: tauri::State<AppState>,
+    app_handle: AppHandle,
+    file_path: String,
+    csv_options: Option<CsvOptions>,
+    sorting: Option<Vec<Sorting>>,
+    filters: Option<Vec<Filter>>,
+    job_id: Option<String>,
+) -> Result<Correlations, String> {
+    state
+        .jobs
+        .run(&app_handle, "get_correlations", job_id, |job| {
+            let key = CacheKey {
+                file_path: Some(file_path.clone()),
+                filters,
+                sorting,
+                csv_options: csv_options_by_path(&file_path, csv_options),
+                ..Default::default()
+            };
+            let lf = file_view(&state, &file_path, &key)?;
+
+            let df = job.collect(select_numeric(lf)?)?;
+            correlations(&df)
+        })
+}
 
-        return Ok(HistogramData {
-            bins,
-            counts,
-            min,
-            max,
-        });
-    }
+#[tauri::command(async)]
+fn get_query_correlations(
+    state: tauri::State<AppState>,
+    app_handle: AppHandle,
+    window: Window,
+    view: ViewRef,
+    job_id: Option<String>,
+) -> Result<Correlations, String> {
+    state
+        .jobs
+        .run(&app_handle, "get_query_correlations", job_id, |job| {
+            let data = view_data(&state, &window, job, view)?;
+            let df = job.collect(select_numeric(data.lazy())?)?;
+            correlations(&df)
+        })
+}
 
-    let bin_width = (max - min) / bins_count as f64;
+fn correlations(df: &DataFrame) -> Result<Correlations, String> {
+    Ok(Correlations {
+        pearson: calculate_correlations(df, CorrelationMethod::Pearson)?,
+        spearman: calculate_correlations(df, CorrelationMethod::Spearman)?,
+    })
+}
 
-    let mut bins = Vec::new();
-    let mut counts = vec![0usize; bins_count];
+// 2-D binned density of two numeric columns of a file view, for a scatter plot or heatmap
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
+fn get_pair_density(
+    state: tauri::State<AppState>,
+    app_handle: AppHandle,
+    file_path: String,
+    x_column: String,
+    y_column: String,
+    num_bins: Option<usize>,
+    csv_options: Option<CsvOptions>,
+    sorting: Option<Vec<Sorting>>,
+    filters: Option<Vec<Filter>>,
+    job_id: Option<String>,
+) -> Result<PairDensity, String> {
+    state
+        .jobs
+        .run(&app_handle, "get_pair_density", job_id, |job| {
+            let key = CacheKey {
+                file_path: Some(file_path.clone()),
+                filters,
+                sorting,
+                csv_options: csv_options_by_path(&file_path, csv_options),
+                ..Default::default()
+            };
+            let lf = file_view(&state, &file_path, &key)?;
+
+            // Only the two columns are read
+            let df = job.collect(lf.select(pair_columns(&x_column, &y_column)))?;
+            calculate_pair_density(&df, &x_column, &y_column, num_bins)
+        })
+}
 
-    // Create bin edges
-    for i in 0..=bins_count {
-        bins.push(min + i as f64 * bin_width);
-    }
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
+fn get_query_pair_density(
+    state: tauri::State<AppState>,
+    app_handle: AppHandle,
+    window: Window,
+    view: ViewRef,
+    x_column: String,
+    y_column: String,
+    num_bins: Option<usize>,
+    job_id: Option<String>,
+) -> Result<PairDensity, String> {
+    state
+        .jobs
+        .run(&app_handle, "get_query_pair_density", job_id, |job| {
+            let data = view_data(&state, &window, job, view)?;
+            let df = job.collect(data.lazy().select(pair_columns(&x_column, &y_column)))?;
+            calculate_pair_density(&df, &x_column, &y_column, num_bins)
+        })
+}
 
-    // Count values in each bin
-    for opt_val in f64_series.into_iter() {
-        if let Some(val) = opt_val {
-            if val >= min && val <= max {
-                let bin_idx = ((val - min) / bin_width).floor() as usize;
-                let bin_idx = bin_idx.min(bins_count - 1); // Handle edge case where val == max
-                counts[bin_idx] += 1;
-            }
-        }
+// Columns to select for a pair density; a column paired with itself is selected once
+fn pair_columns(x_column: &str, y_column: &str) -> Vec<Expr> {
+    if x_column == y_column {
+        vec![col(x_column)]
+    } else {
+        vec![col(x_column), col(y_column)]
     }
-
-    Ok(HistogramData {
-        bins,
-        counts,
-        min,
-        max,
-    })
 }
 
 // Window control commands for custom title bar
-#[tauri::command]
-fn copy_full_table(state: tauri::State<AppState>) -> Result<String, String> {
-    let cache = state.cache.lock().unwrap();
-    if let Some(entry) = cache.as_ref() {
-        let df = &entry.df;
-
-        // Extract column names as header row
-        let columns = extract_columns(df);
-        let header = columns
-            .iter()
-            .map(|col| col.name.clone())
-            .collect::<Vec<_>>()
-            .join("\t");
-
-        // Convert entire dataframe to TSV format
-        let rows = dataframe_to_rows(df)?;
-        let data_rows = rows
-            .iter()
-            .map(|row| row.join("\t"))
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        // Combine header and data
-        let table_data = format!("{}\n{}", header, data_rows);
-
-        Ok(table_data)
-    } else {
-        Err("No data loaded".to_string())
-    }
+#[tauri::command(async)]
+fn copy_full_table(
+    state: tauri::State<AppState>,
+    app_handle: AppHandle,
+    window: Window,
+    view: ViewRef,
+    job_id: Option<String>,
+) -> Result<String, String> {
+    let df = &state
+        .jobs
+        .run(&app_handle, "copy_full_table", job_id, |job| {
+            view_data(&state, &window, job, view)?.to_dataframe(job)
+        })?;
+
+    // Extract column names as header row
+    let columns = extract_columns(df);
+    let header = columns
+        .iter()
+        .map(|col| col.name.clone())
+        .collect::<Vec<_>>()
+        .join("\t");
+
+    // Convert entire dataframe to TSV format
+    let rows = dataframe_to_rows(df)?;
+    let data_rows = rows
+        .iter()
+        .map(|row| row.join("\t"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    // Combine header and data
+    let table_data = format!("{}\n{}", header, data_rows);
+
+    Ok(table_data)
 }
 
 #[tauri::command]
@@ -817,25 +1655,21 @@ fn start_watching(
         return Ok(());
     }
 
-    let path = Path::new(&file_path);
-    if !path.exists() {
+    if !dataset_root(&file_path).exists() {
         return Err(format!("File does not exist: {}", file_path));
     }
+    // Fails for directories and globs without data files
+    let mut files = dataset_files(&file_path)?;
 
-    let parent = path.parent().ok_or("Could not get parent directory")?;
-    let path_to_watch = parent.to_owned();
+    let (path_to_watch, mode) = watch_target(&file_path)?;
     let file_path_clone = file_path.clone();
-    let file_name = path
-        .file_name()
-        .ok_or("Could not get file name")?
-        .to_os_string();
 
     let (tx, rx) = std::sync::mpsc::channel();
 
     let mut watcher = RecommendedWatcher::new(tx, Config::default()).map_err(|e| e.to_string())?;
 
     watcher
-        .watch(&path_to_watch, RecursiveMode::NonRecursive)
+        .watch(&path_to_watch, mode)
         .map_err(|e| e.to_string())?;
 
     watchers.insert(file_path.clone(), watcher);
@@ -845,32 +1679,38 @@ fn start_watching(
         for res in rx {
             match res {
                 Ok(event) => {
+                    if !(event.kind.is_modify() || event.kind.is_create() || event.kind.is_remove())
+                    {
+                        continue;
+                    }
+                    // Files added to a dataset count, and so do files removed from it
+                    let current = dataset_files(&file_path_clone).unwrap_or_default();
                     let matches_path = event
                         .paths
                         .iter()
-                        .any(|p| p.file_name() == Some(&file_name));
+                        .any(|p| current.contains(p) || files.contains(p));
+                    files = current;
 
                     if matches_path {
-                        if event.kind.is_modify() || event.kind.is_create() {
-                            println!(
-                                "File event detected for {}: {:?}",
-                                file_path_clone, event.kind
-                            );
-                            // Invalidate cache if it matches this file
-                            {
-                                let mut cache = state.cache.lock().unwrap();
-                                if let Some(entry) = cache.as_ref() {
-                                    if entry.file_path.as_deref() == Some(&file_path_clone) {
-                                        println!("Invalidating cache for {}", file_path_clone);
-                                        *cache = None;
-                                    }
-                                }
+                        println!(
+                            "File event detected for {}: {:?}",
+                            file_path_clone, event.kind
+                        );
+                        // Invalidate cached views that read this file
+                        {
+                            let mut cache = state.cache.lock().unwrap();
+                            let evicted = cache.evict_file(&file_path_clone);
+                            if evicted > 0 {
+                                println!(
+                                    "Invalidated {} cached views for {}",
+                                    evicted, file_path_clone
+                                );
                             }
-
-                            // Small delay to let the file system settle
-                            std::thread::sleep(std::time::Duration::from_millis(100));
-                            let _ = app_handle.emit("file-changed", &file_path_clone);
                         }
+
+                        // Small delay to let the file system settle
+                        std::thread::sleep(std::time::Duration::from_millis(100));
+                        let _ = app_handle.emit("file-changed", &file_path_clone);
                     }
                 }
                 Err(e) => println!("watch error: {:?}", e),
@@ -885,14 +1725,24 @@ fn start_watching(
 fn stop_watching(state: tauri::State<AppState>, file_path: String) -> Result<(), String> {
     let mut watchers = state.watchers.lock().unwrap();
     if let Some(mut watcher) = watchers.remove(&file_path) {
-        let path = Path::new(&file_path);
-        if let Some(parent) = path.parent() {
-            let _ = watcher.unwatch(parent);
+        if let Ok((path, _)) = watch_target(&file_path) {
+            let _ = watcher.unwatch(&path);
         }
     }
     Ok(())
 }
 
+// What to watch for changes to a path. Directory and glob datasets are watched recursively from
+// their root. A single file is watched through its directory, so replacing the file is seen too.
+fn watch_target(file_path: &str) -> Result<(PathBuf, RecursiveMode), String> {
+    let root = dataset_root(file_path);
+    if root.is_dir() {
+        return Ok((root, RecursiveMode::Recursive));
+    }
+    let parent = root.parent().ok_or("Could not get parent directory")?;
+    Ok((parent.to_path_buf(), RecursiveMode::NonRecursive))
+}
+
 #[tauri::command]
 fn read_text_file(path: String) -> Result<String, String> {
     fs::read_to_string(path).map_err(|e| e.to_string())
@@ -941,21 +1791,46 @@ pub fn run() {
             Ok(())
         })
         .manage(AppState::default())
+        .on_window_event(|window, event| {
+            // A window's SQL session ends with the window
+            if let tauri::WindowEvent::Destroyed = event {
+                let state = window.state::<AppState>();
+                state.sql_sessions.lock().unwrap().remove(window.label());
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             read_parquet,
             get_data,
             get_more_rows,
             execute_sql,
             get_more_sql_rows,
+            explain_sql,
+            list_sql_tables,
+            drop_sql_table,
             get_statistics,
             get_query_statistics,
             get_column_histogram,
             get_query_column_histogram,
+            get_correlations,
+            get_query_correlations,
+            get_pair_density,
+            get_query_pair_density,
+            profile_dataset,
             save_parquet,
             save_csv,
             copy_full_table,
             load_settings,
             save_settings,
+            get_cache_usage,
+            get_schema_tree,
+            get_parquet_metadata,
+            get_column_pages,
+            get_row_groups,
+            locate_row,
+            explain_pruning,
+            cancel_job,
+            load_csv_options,
+            save_csv_options,
             minimize_window,
             maximize_window,
             unmaximize_window,
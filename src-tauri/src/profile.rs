@@ -0,0 +1,557 @@
+use polars::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::data_loader::{
+    calculate_correlations, calculate_histogram_from_dataframe, calculate_statistics_lazy,
+    select_numeric, CorrelationMatrix, CorrelationMethod, HistogramData, HistogramOptions,
+};
+
+const HISTOGRAM_BINS: usize = 20;
+
+/// Number of null patterns listed in a report
+const NULL_PATTERNS: usize = 10;
+
+/// Order in which statistics are listed, matching the statistics popover.
+/// Statistics not listed here follow in alphabetical order.
+const STATISTICS_ORDER: [&str; 21] = [
+    "null_values",
+    "true_count",
+    "false_count",
+    "min",
+    "max",
+    "mean",
+    "percentile_25",
+    "median",
+    "percentile_75",
+    "range",
+    "most_common_weekday",
+    "most_common_hour",
+    "largest_gap",
+    "largest_gap_start",
+    "unique_values",
+    "empty_values",
+    "min_length",
+    "max_length",
+    "mean_length",
+    "patterns",
+    "top_values",
+];
+
+#[derive(Serialize)]
+pub struct ProfileReport {
+    pub source: String,
+    pub generated_at: String,
+    pub rows: usize,
+    pub columns: Vec<ColumnProfile>,
+    pub correlations: CorrelationMatrix,
+    pub null_patterns: Vec<NullPattern>,
+}
+
+/// Set of columns that are null together in some rows
+#[derive(Serialize)]
+pub struct NullPattern {
+    pub columns: Vec<String>,
+    pub rows: usize,
+}
+
+#[derive(Serialize)]
+pub struct ColumnProfile {
+    pub name: String,
+    pub dtype: String,
+    pub statistics: HashMap<String, serde_json::Value>,
+    /// Only for numeric columns
+    pub histogram: Option<HistogramData>,
+}
+
+/// Profile every column of a LazyFrame without collecting it. Statistics are
+/// computed by `calculate_statistics_lazy`; histograms read one column at a
+/// time and correlations the numeric columns. `collect` runs each query.
+pub fn profile_lazy<F>(lf: &LazyFrame, source: &str, collect: F) -> Result<ProfileReport, String>
+where
+    F: Fn(LazyFrame) -> Result<DataFrame, String>,
+{
+    let schema = lf
+        .clone()
+        .collect_schema()
+        .map_err(|e| format!("Failed to read schema: {}", e))?;
+
+    let rows = collect(lf.clone().select([len()]))?
+        .get_columns()
+        .first()
+        .and_then(|column| column.get(0).ok()?.extract::<usize>())
+        .unwrap_or(0);
+
+    let mut statistics = calculate_statistics_lazy(lf, None, HashMap::new(), &collect)?;
+
+    let mut nullable = Vec::new();
+    let mut columns = Vec::new();
+    for (name, dtype) in schema.iter() {
+        let name = name.to_string();
+        let histogram = if dtype.is_primitive_numeric() {
+            let df = collect(lf.clone().select([col(name.as_str())]))?;
+            calculate_histogram_from_dataframe(
+                &df,
+                &name,
+                Some(HISTOGRAM_BINS),
+                &HistogramOptions::default(),
+            )
+            .ok()
+        } else {
+            None
+        };
+
+        let column_statistics = statistics.remove(&name).unwrap_or_default();
+        let nulls = column_statistics
+            .get("null_values")
+            .and_then(|value| value.as_u64());
+        if nulls.is_some_and(|nulls| nulls > 0) {
+            nullable.push(name.clone());
+        }
+
+        columns.push(ColumnProfile {
+            statistics: column_statistics,
+            dtype: format!("{:?}", dtype),
+            name,
+            histogram,
+        });
+    }
+
+    let numeric = collect(select_numeric(lf.clone())?)?;
+
+    Ok(ProfileReport {
+        source: source.to_string(),
+        generated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        rows,
+        columns,
+        correlations: calculate_correlations(&numeric, CorrelationMethod::Pearson)?,
+        null_patterns: calculate_null_patterns(lf, &nullable, NULL_PATTERNS, &collect)?,
+    })
+}
+
+/// Most common combinations of null columns across rows, most frequent first,
+/// among the given columns. Rows without nulls are not counted. The rows are
+/// grouped by their null masks in the query, so only the patterns are
+/// collected.
+pub fn calculate_null_patterns<F>(
+    lf: &LazyFrame,
+    columns: &[String],
+    limit: usize,
+    collect: F,
+) -> Result<Vec<NullPattern>, String>
+where
+    F: Fn(LazyFrame) -> Result<DataFrame, String>,
+{
+    // Column names may contain anything, so masks are named by position
+    let mask_name = |i: usize| format!("null_{}", i);
+    let masks: Vec<Expr> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, name)| col(name.as_str()).is_null().alias(mask_name(i)))
+        .collect();
+    if columns.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Most rows first; ties are ordered by the first column that is null in
+    // one pattern and not the other. Rows without nulls form one more group,
+    // which is dropped after collecting.
+    let mut order = vec!["rows".to_string()];
+    order.extend((0..columns.len()).map(mask_name));
+    let patterns = collect(
+        lf.clone()
+            .select(masks)
+            .group_by(
+                (0..columns.len())
+                    .map(|i| col(mask_name(i)))
+                    .collect::<Vec<_>>(),
+            )
+            .agg([len().alias("rows")])
+            .sort(
+                order,
+                SortMultipleOptions::default().with_order_descending(true),
+            )
+            .limit(limit as IdxSize + 1),
+    )?;
+
+    let rows = patterns
+        .column("rows")
+        .and_then(|column| column.cast(&DataType::UInt64))
+        .map_err(|e| format!("Failed to count null patterns: {}", e))?;
+    let rows = rows
+        .u64()
+        .map_err(|e| format!("Failed to count null patterns: {}", e))?;
+    let mut masks = Vec::with_capacity(columns.len());
+    for i in 0..columns.len() {
+        let mask = patterns
+            .column(&mask_name(i))
+            .and_then(|column| column.bool().cloned())
+            .map_err(|e| format!("Failed to read null patterns: {}", e))?;
+        masks.push(mask);
+    }
+
+    Ok((0..patterns.height())
+        .map(|row| NullPattern {
+            columns: columns
+                .iter()
+                .zip(&masks)
+                .filter(|(_, mask)| mask.get(row).unwrap_or(false))
+                .map(|(name, _)| name.clone())
+                .collect(),
+            rows: rows.get(row).unwrap_or(0) as usize,
+        })
+        .filter(|pattern| !pattern.columns.is_empty())
+        .take(limit)
+        .collect())
+}
+
+/// Write a report as JSON or self-contained HTML, depending on the extension
+/// of the output path
+pub fn write_report(report: &ProfileReport, output_path: &str) -> Result<(), String> {
+    let extension = Path::new(output_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+
+    let contents = match extension.as_deref() {
+        Some("json") => serde_json::to_string_pretty(report)
+            .map_err(|e| format!("Failed to serialize report: {}", e))?,
+        Some("html") | Some("htm") => render_html(report),
+        _ => {
+            return Err(format!(
+                "Unsupported report format: {} (expected .html or .json)",
+                output_path
+            ))
+        }
+    };
+
+    // Create parent directories if they don't exist
+    if let Some(parent) = Path::new(output_path).parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    fs::write(output_path, contents).map_err(|e| format!("Failed to write report: {}", e))
+}
+
+fn render_html(report: &ProfileReport) -> String {
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!(
+        "<title>Profile of {}</title>\n",
+        escape_html(&report.source)
+    ));
+    html.push_str(REPORT_STYLE);
+    html.push_str("</head>\n<body>\n");
+
+    // Overview
+    html.push_str(&format!(
+        "<h1>Profile of {}</h1>\n<p class=\"meta\">{} rows, {} columns. Generated {}.</p>\n",
+        escape_html(&report.source),
+        report.rows,
+        report.columns.len(),
+        escape_html(&report.generated_at)
+    ));
+
+    // Columns
+    html.push_str("<h2>Columns</h2>\n");
+    for column in &report.columns {
+        html.push_str(&format!(
+            "<section class=\"column\">\n<h3>{} <span class=\"dtype\">{}</span></h3>\n",
+            escape_html(&column.name),
+            escape_html(&column.dtype)
+        ));
+        html.push_str("<div class=\"column-body\">\n<table class=\"stats\">\n");
+        for (key, value) in ordered_statistics(&column.statistics) {
+            html.push_str(&format!(
+                "<tr><th>{}</th><td>{}</td></tr>\n",
+                escape_html(&key.replace('_', " ")),
+                render_value(value)
+            ));
+        }
+        html.push_str("</table>\n");
+        if let Some(histogram) = &column.histogram {
+            html.push_str(&render_histogram(histogram));
+        }
+        html.push_str("</div>\n</section>\n");
+    }
+
+    // Correlations
+    html.push_str("<h2>Correlations</h2>\n");
+    let correlations = &report.correlations;
+    if correlations.columns.len() < 2 {
+        html.push_str("<p class=\"meta\">Fewer than two numeric columns.</p>\n");
+    } else {
+        html.push_str("<table class=\"matrix\">\n<tr><th></th>");
+        for name in &correlations.columns {
+            html.push_str(&format!("<th>{}</th>", escape_html(name)));
+        }
+        html.push_str("</tr>\n");
+        for (name, row) in correlations.columns.iter().zip(&correlations.values) {
+            html.push_str(&format!("<tr><th>{}</th>", escape_html(name)));
+            for value in row {
+                match value {
+                    Some(r) => html.push_str(&format!(
+                        "<td style=\"background:{}\">{:.2}</td>",
+                        correlation_color(*r),
+                        r
+                    )),
+                    None => html.push_str("<td></td>"),
+                }
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n");
+    }
+
+    // Null patterns
+    html.push_str("<h2>Null patterns</h2>\n");
+    if report.null_patterns.is_empty() {
+        html.push_str("<p class=\"meta\">No null values.</p>\n");
+    } else {
+        html.push_str("<table class=\"stats\">\n<tr><th>Null columns</th><th>Rows</th></tr>\n");
+        for pattern in &report.null_patterns {
+            let columns: Vec<String> = pattern.columns.iter().map(|c| escape_html(c)).collect();
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td></tr>\n",
+                columns.join(", "),
+                pattern.rows
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn ordered_statistics(
+    statistics: &HashMap<String, serde_json::Value>,
+) -> Vec<(&String, &serde_json::Value)> {
+    let mut entries: Vec<(&String, &serde_json::Value)> = statistics.iter().collect();
+    entries.sort_by_key(|(key, _)| {
+        let rank = STATISTICS_ORDER
+            .iter()
+            .position(|k| k == key)
+            .unwrap_or(STATISTICS_ORDER.len());
+        (rank, key.to_string())
+    });
+    entries
+}
+
+fn render_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Number(n) => match n.as_f64() {
+            Some(f) if n.is_f64() => format!("{:.4}", f),
+            _ => n.to_string(),
+        },
+        serde_json::Value::String(s) => escape_html(s),
+        // Top values: [{ value, count }, ...]
+        serde_json::Value::Array(items) => {
+            let items: Vec<String> = items
+                .iter()
+                .map(|item| {
+                    let text = match item.get("value") {
+                        Some(serde_json::Value::String(s)) if s.is_empty() => "(empty)".to_string(),
+                        Some(serde_json::Value::String(s)) => escape_html(s),
+                        Some(other) => escape_html(&other.to_string()),
+                        None => escape_html(&item.to_string()),
+                    };
+                    match item.get("count") {
+                        Some(count) => {
+                            format!("<li>{} <span class=\"count\">{}</span></li>", text, count)
+                        }
+                        None => format!("<li>{}</li>", text),
+                    }
+                })
+                .collect();
+            format!("<ol>{}</ol>", items.join(""))
+        }
+        other => escape_html(&other.to_string()),
+    }
+}
+
+/// Inline SVG bar chart of a histogram
+fn render_histogram(histogram: &HistogramData) -> String {
+    const WIDTH: f64 = 280.0;
+    const HEIGHT: f64 = 120.0;
+
+    let max_count = histogram.counts.iter().copied().max().unwrap_or(0).max(1) as f64;
+    let bar_width = WIDTH / histogram.counts.len().max(1) as f64;
+
+    let mut svg = format!(
+        "<figure class=\"histogram\">\n<svg viewBox=\"0 0 {} {}\" width=\"{}\" height=\"{}\">\n",
+        WIDTH, HEIGHT, WIDTH, HEIGHT
+    );
+    for (i, count) in histogram.counts.iter().enumerate() {
+        let bar_height = *count as f64 / max_count * HEIGHT;
+        svg.push_str(&format!(
+            "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\"><title>{}</title></rect>\n",
+            i as f64 * bar_width,
+            HEIGHT - bar_height,
+            (bar_width - 1.0).max(0.5),
+            bar_height,
+            count
+        ));
+    }
+    svg.push_str(&format!(
+        "</svg>\n<figcaption><span>{}</span><span>{}</span></figcaption>\n</figure>\n",
+        histogram.min.unwrap_or_default(),
+        histogram.max.unwrap_or_default()
+    ));
+    svg
+}
+
+/// Blue for positive, red for negative correlations
+fn correlation_color(r: f64) -> String {
+    let alpha = r.abs().min(1.0) * 0.8;
+    if r >= 0.0 {
+        format!("rgba(37, 99, 235, {:.2})", alpha)
+    } else {
+        format!("rgba(220, 38, 38, {:.2})", alpha)
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+const REPORT_STYLE: &str = r#"<style>
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; margin: 2rem; color: #1f2937; }
+h1 { font-size: 1.5rem; margin-bottom: 0.25rem; }
+h2 { font-size: 1.2rem; margin-top: 2rem; border-bottom: 1px solid #e5e7eb; padding-bottom: 0.25rem; }
+h3 { font-size: 1rem; margin: 0 0 0.5rem; }
+.meta { color: #6b7280; }
+.dtype { color: #6b7280; font-weight: normal; font-family: monospace; }
+.column { border: 1px solid #e5e7eb; border-radius: 6px; padding: 1rem; margin-bottom: 1rem; }
+.column-body { display: flex; flex-wrap: wrap; gap: 2rem; align-items: flex-start; }
+table { border-collapse: collapse; font-size: 0.875rem; }
+.stats th { text-align: left; font-weight: normal; color: #6b7280; padding: 0.15rem 1rem 0.15rem 0; text-transform: capitalize; }
+.stats td { font-family: monospace; padding: 0.15rem 0; }
+.stats ol { margin: 0; padding-left: 1.25rem; }
+.count { color: #6b7280; }
+.matrix th, .matrix td { border: 1px solid #e5e7eb; padding: 0.25rem 0.5rem; text-align: center; font-family: monospace; }
+.histogram { margin: 0; }
+.histogram rect { fill: #2563eb; }
+.histogram figcaption { display: flex; justify-content: space-between; font-size: 0.75rem; color: #6b7280; font-family: monospace; }
+</style>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(lf: LazyFrame) -> Result<DataFrame, String> {
+        lf.collect().map_err(|e| e.to_string())
+    }
+
+    fn names(df: &DataFrame) -> Vec<String> {
+        df.get_column_names()
+            .iter()
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn null_patterns_are_counted_by_row() {
+        let df = df!(
+            "a" => [None, None, Some(1), None, Some(4)],
+            "b" => [None, None, Some(1), Some(2), Some(4)],
+            "c" => [Some(1), Some(2), Some(3), None, Some(4)],
+            "d" => [1, 2, 3, 4, 5],
+        )
+        .unwrap();
+        let lf = df.clone().lazy();
+
+        let patterns: Vec<(Vec<String>, usize)> =
+            calculate_null_patterns(&lf, &names(&df), 10, collect)
+                .unwrap()
+                .into_iter()
+                .map(|pattern| (pattern.columns, pattern.rows))
+                .collect();
+        assert_eq!(
+            patterns,
+            vec![
+                (vec!["a".to_string(), "b".to_string()], 2),
+                (vec!["a".to_string(), "c".to_string()], 1),
+            ]
+        );
+        assert_eq!(
+            calculate_null_patterns(&lf, &names(&df), 1, collect)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(calculate_null_patterns(&lf, &[], 10, collect)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn null_patterns_span_more_than_64_columns() {
+        let columns: Vec<Column> = (0..70)
+            .map(|i| {
+                let values = if i == 0 || i == 69 {
+                    [None, Some(1)]
+                } else {
+                    [Some(1), Some(1)]
+                };
+                Column::new(format!("c{}", i).into(), values)
+            })
+            .collect();
+        let df = DataFrame::new(columns).unwrap();
+
+        let patterns =
+            calculate_null_patterns(&df.clone().lazy(), &names(&df), 10, collect).unwrap();
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].columns, vec!["c0", "c69"]);
+        assert_eq!(patterns[0].rows, 1);
+    }
+
+    #[test]
+    fn profiles_describe_the_rows_of_the_view() {
+        let lf = df!(
+            "x" => [Some(1i64), Some(2), None, Some(4), Some(5)],
+            "y" => [Some(2.0), None, None, Some(8.0), Some(10.0)],
+            "label" => ["a", "b", "c", "d", "e"],
+        )
+        .unwrap()
+        .lazy()
+        .filter(col("label").neq(lit("e")));
+
+        let report = profile_lazy(&lf, "view", collect).unwrap();
+        assert_eq!(report.rows, 4);
+        let columns: Vec<&str> = report.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(columns, ["x", "y", "label"]);
+        assert_eq!(report.columns[0].statistics["max"], serde_json::json!(4.0));
+        assert!(report.columns[0].histogram.is_some());
+        assert!(report.columns[2].histogram.is_none());
+        assert_eq!(report.correlations.columns, ["x", "y"]);
+
+        let patterns: Vec<(Vec<String>, usize)> = report
+            .null_patterns
+            .into_iter()
+            .map(|pattern| (pattern.columns, pattern.rows))
+            .collect();
+        assert_eq!(
+            patterns,
+            vec![
+                (vec!["x".to_string(), "y".to_string()], 1),
+                (vec!["y".to_string()], 1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use polars::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Logical plans of a query, before and after optimization
+#[derive(Serialize)]
+pub struct QueryPlan {
+    pub unoptimized: String,
+    pub optimized: String,
+    /// Optimized plan as a tree, with the scans at the leaves
+    pub tree: PlanNode,
+}
+
+#[derive(Serialize)]
+pub struct PlanNode {
+    /// Description of the step, one detail per line
+    pub label: String,
+    pub children: Vec<PlanNode>,
+}
+
+/// Describe the plans of a LazyFrame. Scans in the optimized plan show the
+/// columns and predicates pushed down into them.
+pub fn explain_query(lf: &LazyFrame) -> Result<QueryPlan, String> {
+    let describe_error = |e: PolarsError| format!("Failed to describe query plan: {}", e);
+
+    let unoptimized = lf.describe_plan().map_err(describe_error)?;
+    let optimized = lf.describe_optimized_plan().map_err(describe_error)?;
+    let dot = lf.to_dot(true).map_err(describe_error)?;
+
+    Ok(QueryPlan {
+        unoptimized,
+        optimized,
+        tree: plan_tree(&dot)?,
+    })
+}
+
+/// Build the tree from the Graphviz rendering of a plan, which has a labelled
+/// node per step and an edge from each input to the step that consumes it.
+/// The optimized IR is not exported by the polars crate, so this is the one
+/// per-step view of it. A step read twice, like both sides of a self-union,
+/// has one edge per read and is listed under its consumer each time.
+fn plan_tree(dot: &str) -> Result<PlanNode, String> {
+    let mut labels: HashMap<&str, String> = HashMap::new();
+    let mut inputs: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut consumed = Vec::new();
+    let mut order = Vec::new();
+
+    for line in dot.lines().map(str::trim) {
+        if let Some((id, rest)) = line.split_once("[label=\"") {
+            let label = rest.strip_suffix("\"]").unwrap_or(rest);
+            labels.insert(node_id(id), unescape(label));
+            order.push(node_id(id));
+        } else if let Some((input, step)) = line.split_once(" -> ") {
+            let (input, step) = (node_id(input), node_id(step));
+            inputs.entry(step).or_default().push(input);
+            consumed.push(input);
+        }
+    }
+
+    let root = order
+        .iter()
+        .find(|id| !consumed.contains(id))
+        .ok_or_else(|| "Failed to describe query plan: no root step".to_string())?;
+
+    Ok(plan_node(root, &labels, &inputs))
+}
+
+fn plan_node(
+    id: &str,
+    labels: &HashMap<&str, String>,
+    inputs: &HashMap<&str, Vec<&str>>,
+) -> PlanNode {
+    PlanNode {
+        label: labels.get(id).cloned().unwrap_or_default(),
+        children: inputs
+            .get(id)
+            .map(|ids| {
+                ids.iter()
+                    .map(|input| plan_node(input, labels, inputs))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+fn node_id(id: &str) -> &str {
+    id.trim().trim_matches('"')
+}
+
+/// Undo the escaping of a Graphviz label
+fn unescape(label: &str) -> String {
+    let mut text = String::with_capacity(label.len());
+    let mut chars = label.chars();
+    while let Some(ch) = chars.next() {
+        match (ch, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                text.push('\n');
+                chars.next();
+            }
+            ('\\', Some(escaped @ ('"' | '\\'))) => {
+                text.push(escaped);
+                chars.next();
+            }
+            _ => text.push(ch),
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_loader::test_utils::{path_str, temp_dir, write_parquet};
+
+    fn labels(node: &PlanNode, depth: usize, out: &mut Vec<(usize, String)>) {
+        let first_line = node.label.lines().next().unwrap_or_default();
+        out.push((depth, first_line.to_string()));
+        for child in &node.children {
+            labels(child, depth + 1, out);
+        }
+    }
+
+    #[test]
+    fn tree_of_a_join_has_the_scans_at_its_leaves() {
+        let dir = temp_dir("query-plan-join");
+        let (left, right) = (dir.join("left.parquet"), dir.join("right.parquet"));
+        let mut df = df!("id" => [1i64, 2, 3], "x" => [10i64, 20, 30]).unwrap();
+        write_parquet(&left, &mut df, 10);
+        let mut df = df!("id" => [1i64, 2, 4], "y" => [5i64, 25, 40]).unwrap();
+        write_parquet(&right, &mut df, 10);
+
+        let scan = |path| {
+            LazyFrame::scan_parquet(PlPath::new(path_str(path)), ScanArgsParquet::default())
+                .unwrap()
+        };
+        let lf = scan(&left)
+            .join(
+                scan(&right),
+                [col("id")],
+                [col("id")],
+                JoinArgs::new(JoinType::Inner),
+            )
+            // Pushed down into the left scan
+            .filter(col("x").gt(lit(10)))
+            // Reads both sides, so it stays above the join
+            .filter(col("x").gt(col("y")));
+
+        let plan = explain_query(&lf).unwrap();
+        let mut steps = Vec::new();
+        labels(&plan.tree, 0, &mut steps);
+
+        let join = steps
+            .iter()
+            .position(|(_, label)| label.starts_with("JOIN INNER"))
+            .unwrap();
+        let filter = steps
+            .iter()
+            .position(|(_, label)| label.starts_with("FILTER"))
+            .unwrap();
+        assert!(steps[filter].0 < steps[join].0);
+
+        let scans: Vec<_> = steps
+            .iter()
+            .filter(|(_, label)| label.contains("SCAN"))
+            .collect();
+        assert_eq!(scans.len(), 2);
+        assert!(scans.iter().all(|(depth, _)| *depth == steps[join].0 + 1));
+
+        // The pushed-down predicate is shown on the left scan only
+        let left_scan = find(&plan.tree, "left.parquet").unwrap();
+        assert!(left_scan.children.is_empty());
+        assert!(left_scan.label.contains("σ"));
+        assert!(!find(&plan.tree, "right.parquet")
+            .unwrap()
+            .label
+            .contains("σ"));
+    }
+
+    #[test]
+    fn shared_steps_are_listed_once_per_read() {
+        // The inputs of a shared step are written once, its edges once per read
+        let dot = r#"digraph polars_query {
+  p2 -> p1
+  "c1" -> p2
+  p4 -> "c1"
+  p4[label="SCAN"]
+  "c1"[label="CACHE"]
+  "c1" -> p2
+  p2[label="UNION"]
+  p1[label="FILTER BY [(col(\"a\")) > (1)]\nFROM"]
+}"#;
+
+        let tree = plan_tree(dot).unwrap();
+        assert_eq!(tree.label, "FILTER BY [(col(\"a\")) > (1)]\nFROM");
+        assert_eq!(tree.children.len(), 1);
+        let union = &tree.children[0];
+        assert_eq!(union.children.len(), 2);
+        for cache in &union.children {
+            assert_eq!(cache.label, "CACHE");
+            assert_eq!(cache.children.len(), 1);
+            assert_eq!(cache.children[0].label, "SCAN");
+        }
+    }
+
+    #[test]
+    fn self_union_lists_both_inputs() {
+        let dir = temp_dir("query-plan-self-union");
+        let path = dir.join("t.parquet");
+        let mut df = df!("x" => [1i64, 2, 3]).unwrap();
+        write_parquet(&path, &mut df, 10);
+
+        let mut ctx = polars::sql::SQLContext::new();
+        let scan =
+            LazyFrame::scan_parquet(PlPath::new(path_str(&path)), ScanArgsParquet::default());
+        ctx.register("t", scan.unwrap());
+        let lf = ctx
+            .execute("SELECT * FROM t UNION ALL SELECT * FROM t")
+            .unwrap();
+
+        let plan = explain_query(&lf).unwrap();
+        let mut steps = Vec::new();
+        labels(&plan.tree, 0, &mut steps);
+        assert!(steps[0].1.starts_with("UNION"));
+        assert_eq!(plan.tree.children.len(), 2);
+        // Each input reads the file
+        let scans: Vec<_> = steps
+            .iter()
+            .filter(|(_, label)| label.contains("t.parquet"))
+            .collect();
+        assert_eq!(scans.len(), 2);
+    }
+
+    fn find<'a>(node: &'a PlanNode, text: &str) -> Option<&'a PlanNode> {
+        if node.label.contains(text) {
+            return Some(node);
+        }
+        node.children.iter().find_map(|child| find(child, text))
+    }
+}
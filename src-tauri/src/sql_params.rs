@@ -0,0 +1,277 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlparser::ast::{CastKind, DataType, Expr, TimezoneInfo, UnaryOperator, Value};
+use sqlparser::dialect::GenericDialect;
+use sqlparser::tokenizer::{Token, Tokenizer};
+use std::collections::{HashMap, HashSet};
+
+/// Value bound to a named parameter of a SQL query. Dates and timestamps are
+/// given as ISO 8601 strings.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum SqlParameter {
+    Null,
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Date(String),
+    Datetime(String),
+}
+
+impl SqlParameter {
+    /// The value as a SQL expression, rendered by the parser Polars reads SQL
+    /// with so strings are escaped the way Polars unescapes them. Dates and
+    /// timestamps are parsed and written out again. The expression is wrapped
+    /// in parentheses, so `x - :n` with a negative number is not a comment.
+    fn expr(&self, name: &str) -> Result<Expr, String> {
+        let expr = match self {
+            SqlParameter::Null => Expr::Value(Value::Null),
+            SqlParameter::Boolean(value) => Expr::Value(Value::Boolean(*value)),
+            SqlParameter::Integer(value) => number(value.to_string()),
+            SqlParameter::Float(value) => {
+                if !value.is_finite() {
+                    return Err(format!(
+                        "SQL parameter :{} must be a finite number, got {}",
+                        name, value
+                    ));
+                }
+                number(format!("{:?}", value))
+            }
+            SqlParameter::String(value) => Expr::Value(Value::SingleQuotedString(value.clone())),
+            SqlParameter::Date(value) => {
+                let date = NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| {
+                    format!(
+                        "SQL parameter :{} must be a date (YYYY-MM-DD), got '{}'",
+                        name, value
+                    )
+                })?;
+                cast(date.format("%Y-%m-%d").to_string(), DataType::Date)
+            }
+            SqlParameter::Datetime(value) => {
+                let datetime = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+                    .iter()
+                    .find_map(|format| NaiveDateTime::parse_from_str(value.trim(), format).ok())
+                    .ok_or_else(|| {
+                        format!(
+                            "SQL parameter :{} must be a timestamp (YYYY-MM-DD HH:MM:SS), got '{}'",
+                            name, value
+                        )
+                    })?;
+                cast(
+                    datetime.format("%Y-%m-%dT%H:%M:%S%.f").to_string(),
+                    DataType::Timestamp(None, TimezoneInfo::None),
+                )
+            }
+        };
+        Ok(Expr::Nested(Box::new(expr)))
+    }
+}
+
+/// A number, with its sign as an operator like the parser reads it
+fn number(text: String) -> Expr {
+    match text.strip_prefix('-') {
+        Some(digits) => Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr: Box::new(Expr::Value(Value::Number(digits.to_string(), false))),
+        },
+        None => Expr::Value(Value::Number(text, false)),
+    }
+}
+
+fn cast(text: String, data_type: DataType) -> Expr {
+    Expr::Cast {
+        kind: CastKind::Cast,
+        expr: Box::new(Expr::Value(Value::SingleQuotedString(text))),
+        data_type,
+        format: None,
+    }
+}
+
+/// Bind the named parameters (`:name`) of a query to their values. The query
+/// is split with the tokenizer Polars parses SQL with, so `:name` inside
+/// string literals, quoted identifiers and comments is left alone. Every
+/// parameter must have a value, and every value must be used.
+pub fn bind_parameters(
+    query: &str,
+    parameters: &Option<HashMap<String, SqlParameter>>,
+) -> Result<String, String> {
+    let no_parameters = HashMap::new();
+    let parameters = parameters.as_ref().unwrap_or(&no_parameters);
+    if parameters.is_empty() && !query.contains(':') {
+        return Ok(query.to_string());
+    }
+
+    // Queries that do not tokenize are left for Polars to report
+    let Ok(tokens) = Tokenizer::new(&GenericDialect {}, query).tokenize_with_location() else {
+        return Ok(query.to_string());
+    };
+
+    let mut bound = String::with_capacity(query.len());
+    let mut copied = 0;
+    let mut used = HashSet::new();
+    for pair in tokens.windows(2) {
+        let (Token::Colon, Token::Word(word)) = (&pair[0].token, &pair[1].token) else {
+            continue;
+        };
+        if word.quote_style.is_some() || pair[0].span.end != pair[1].span.start {
+            continue;
+        }
+
+        let value = parameters
+            .get(&word.value)
+            .ok_or_else(|| format!("Missing value for SQL parameter :{}", word.value))?;
+        used.insert(word.value.as_str());
+
+        let start = byte_offset(query, pair[0].span.start.line, pair[0].span.start.column);
+        let end = byte_offset(query, pair[1].span.end.line, pair[1].span.end.column);
+        bound.push_str(&query[copied..start]);
+        bound.push_str(&value.expr(&word.value)?.to_string());
+        copied = end;
+    }
+    bound.push_str(&query[copied..]);
+
+    let mut unknown: Vec<_> = parameters
+        .keys()
+        .filter(|name| !used.contains(name.as_str()))
+        .map(|name| format!(":{}", name))
+        .collect();
+    if !unknown.is_empty() {
+        unknown.sort();
+        return Err(format!(
+            "Unknown SQL parameters, not used by the query: {}",
+            unknown.join(", ")
+        ));
+    }
+
+    Ok(bound)
+}
+
+/// Byte offset of a 1-based line and character column
+fn byte_offset(query: &str, line: u64, column: u64) -> usize {
+    let line_start: usize = query
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1) as usize)
+        .map(str::len)
+        .sum();
+    query[line_start..]
+        .char_indices()
+        .nth(column.saturating_sub(1) as usize)
+        .map_or(query.len(), |(offset, _)| line_start + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bind(query: &str, parameters: &[(&str, SqlParameter)]) -> Result<String, String> {
+        let parameters = parameters
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+        bind_parameters(query, &Some(parameters))
+    }
+
+    #[test]
+    fn values_are_bound_as_literals() {
+        let bound = bind(
+            "SELECT * FROM t WHERE name = :name AND day >= :day AND ok = :ok",
+            &[
+                ("name", SqlParameter::String("O'Brien".to_string())),
+                ("day", SqlParameter::Date(" 2024-01-05".to_string())),
+                ("ok", SqlParameter::Boolean(true)),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            bound,
+            "SELECT * FROM t WHERE name = ('O''Brien') AND day >= (CAST('2024-01-05' AS DATE)) \
+             AND ok = (true)"
+        );
+
+        let bound = bind(
+            "SELECT :at, :x, :nothing",
+            &[
+                (
+                    "at",
+                    SqlParameter::Datetime("2024-01-05 10:30:00".to_string()),
+                ),
+                ("x", SqlParameter::Float(1.5)),
+                ("nothing", SqlParameter::Null),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            bound,
+            "SELECT (CAST('2024-01-05T10:30:00' AS TIMESTAMP)), (1.5), (NULL)"
+        );
+    }
+
+    #[test]
+    fn negative_numbers_do_not_start_a_comment() {
+        let bound = bind("SELECT x - :n FROM t", &[("n", SqlParameter::Integer(-5))]).unwrap();
+        assert_eq!(bound, "SELECT x - (-5) FROM t");
+
+        let bound = bind("SELECT x-:n", &[("n", SqlParameter::Float(-0.25))]).unwrap();
+        assert_eq!(bound, "SELECT x-(-0.25)");
+    }
+
+    #[test]
+    fn names_in_strings_comments_and_casts_are_left_alone() {
+        let query = "SELECT ':a', \":a\", x::int -- :a\nFROM t /* :a */ WHERE y = :a";
+        let bound = bind(query, &[("a", SqlParameter::Integer(1))]).unwrap();
+        assert_eq!(
+            bound,
+            "SELECT ':a', \":a\", x::int -- :a\nFROM t /* :a */ WHERE y = (1)"
+        );
+
+        // Without parameters, a query is returned as it is
+        assert_eq!(
+            bind_parameters("SELECT ':a'", &None).unwrap(),
+            "SELECT ':a'"
+        );
+    }
+
+    #[test]
+    fn parameters_are_found_after_multibyte_text_on_earlier_lines() {
+        let query = "SELECT 'déjà vu' AS é,\n  'ü' AS ü, :v AS v\nFROM t WHERE w = :w";
+        let bound = bind(
+            query,
+            &[
+                ("v", SqlParameter::String("x".to_string())),
+                ("w", SqlParameter::Integer(2)),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            bound,
+            "SELECT 'déjà vu' AS é,\n  'ü' AS ü, ('x') AS v\nFROM t WHERE w = (2)"
+        );
+    }
+
+    #[test]
+    fn missing_unknown_and_invalid_values_are_errors() {
+        let error = bind("SELECT :a", &[]).unwrap_err();
+        assert!(error.contains(":a"), "{}", error);
+
+        let error = bind(
+            "SELECT :a",
+            &[
+                ("a", SqlParameter::Integer(1)),
+                ("b", SqlParameter::Integer(2)),
+                ("c", SqlParameter::Integer(3)),
+            ],
+        )
+        .unwrap_err();
+        assert!(error.ends_with(":b, :c"), "{}", error);
+
+        let error = bind(
+            "SELECT :d",
+            &[("d", SqlParameter::Date("5/1/2024".to_string()))],
+        );
+        assert!(error.unwrap_err().contains("must be a date"));
+
+        let error = bind("SELECT :f", &[("f", SqlParameter::Float(f64::NAN))]);
+        assert!(error.unwrap_err().contains("finite"));
+    }
+}
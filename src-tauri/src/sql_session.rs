@@ -0,0 +1,424 @@
+use polars::prelude::*;
+use polars::sql::SQLContext;
+use serde::Serialize;
+use sqlparser::dialect::GenericDialect;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionTableKind {
+    /// Query that is planned again against the open files every time it is used
+    View,
+    /// Query result collected when the table was created
+    Table,
+}
+
+/// Statement that changes the views and tables of a session. Other
+/// statements are run by Polars.
+pub enum SessionStatement {
+    Create {
+        name: String,
+        kind: SessionTableKind,
+        or_replace: bool,
+        query: String,
+    },
+    /// Drops views and tables alike
+    Drop { name: String, if_exists: bool },
+}
+
+impl SessionStatement {
+    /// Query that shows the result of the statement: the rows of the view or
+    /// table it creates. Dropping has no result.
+    pub fn result_query(&self) -> Option<String> {
+        match self {
+            SessionStatement::Create { name, .. } => Some(format!("SELECT * FROM \"{}\"", name)),
+            SessionStatement::Drop { .. } => None,
+        }
+    }
+}
+
+/// A view or temporary table listed in the sidebar
+#[derive(Serialize)]
+pub struct SessionTableInfo {
+    pub name: String,
+    pub kind: SessionTableKind,
+    pub query: String,
+    /// Row count of temporary tables
+    pub rows: Option<usize>,
+}
+
+/// View that could not be planned when the session was registered, e.g.
+/// because a file it reads was closed
+#[derive(Debug)]
+pub struct BrokenView {
+    pub name: String,
+    /// Error of the queries that read the view
+    pub error: String,
+}
+
+#[derive(Clone)]
+struct SessionTable {
+    name: String,
+    kind: SessionTableKind,
+    query: String,
+    /// Collected rows of a temporary table
+    data: Option<DataFrame>,
+}
+
+/// Views and temporary tables of the SQL session of one window. They are
+/// registered in every SQL context built for the window, after the open files,
+/// in the order they were created so each can read the ones before it.
+#[derive(Default, Clone)]
+pub struct SqlSession {
+    tables: Vec<SessionTable>,
+}
+
+impl SqlSession {
+    /// Register the views and tables in a SQL context. Views that cannot be
+    /// planned are left out and returned, so only the queries that read them
+    /// fail; see `broken_view_error`.
+    pub fn register(&self, ctx: &mut SQLContext) -> Vec<BrokenView> {
+        let mut broken = Vec::new();
+        for table in &self.tables {
+            let lf = match &table.data {
+                Some(df) => df.clone().lazy(),
+                None => match ctx.execute(&table.query) {
+                    Ok(lf) => lf,
+                    Err(e) => {
+                        // A view over a broken view fails with the error of that view
+                        let error = match referenced_view(&table.query, &broken) {
+                            Some(view) => view.error.clone(),
+                            None => format!(
+                                "Failed to plan view {}: {}. Drop it with DROP VIEW \"{}\"",
+                                table.name, e, table.name
+                            ),
+                        };
+                        broken.push(BrokenView {
+                            name: table.name.clone(),
+                            error,
+                        });
+                        continue;
+                    }
+                },
+            };
+            ctx.register(&table.name, lf);
+        }
+        broken
+    }
+
+    /// Memory held by the collected rows of the temporary tables, leaving out
+    /// the table that `replacing` would replace
+    pub fn table_bytes(&self, replacing: Option<&str>) -> usize {
+        self.tables
+            .iter()
+            .filter(|table| Some(table.name.as_str()) != replacing)
+            .filter_map(|table| table.data.as_ref())
+            .map(|df| df.estimated_size())
+            .sum()
+    }
+
+    /// Add a view, or a temporary table with its collected rows
+    pub fn create(
+        &mut self,
+        name: &str,
+        kind: SessionTableKind,
+        or_replace: bool,
+        query: &str,
+        data: Option<DataFrame>,
+    ) -> Result<(), String> {
+        if let Some(idx) = self.position(name) {
+            if !or_replace {
+                return Err(format!("Table already exists: {}", name));
+            }
+            self.tables.remove(idx);
+        }
+
+        self.tables.push(SessionTable {
+            name: name.to_string(),
+            kind,
+            query: query.to_string(),
+            data,
+        });
+        Ok(())
+    }
+
+    /// Remove a view or temporary table. Returns false if there is none with
+    /// this name.
+    pub fn drop_table(&mut self, name: &str) -> bool {
+        match self.position(name) {
+            Some(idx) => {
+                self.tables.remove(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn list(&self) -> Vec<SessionTableInfo> {
+        self.tables
+            .iter()
+            .map(|table| SessionTableInfo {
+                name: table.name.clone(),
+                kind: table.kind,
+                query: table.query.clone(),
+                rows: table.data.as_ref().map(|df| df.height()),
+            })
+            .collect()
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.tables.iter().position(|table| table.name == name)
+    }
+}
+
+/// Error of a query that failed in a context the session was registered in:
+/// the error of the first broken view the query reads, otherwise `error`
+pub fn broken_view_error(query: &str, error: String, broken: &[BrokenView]) -> String {
+    match referenced_view(query, broken) {
+        Some(view) => view.error.clone(),
+        None => error,
+    }
+}
+
+fn referenced_view<'a>(query: &str, broken: &'a [BrokenView]) -> Option<&'a BrokenView> {
+    if broken.is_empty() {
+        return None;
+    }
+    let tokens = Tokenizer::new(&GenericDialect {}, query).tokenize().ok()?;
+    tokens.iter().find_map(|token| match token {
+        Token::Word(word) => broken.iter().find(|view| view.name == word.value),
+        _ => None,
+    })
+}
+
+/// Recognise `CREATE [OR REPLACE] [TEMP|TEMPORARY] VIEW|TABLE name AS query`
+/// and `DROP VIEW|TABLE [IF EXISTS] name`. Keywords are case-insensitive and
+/// names may be double-quoted.
+pub fn parse_session_statement(sql: &str) -> Option<SessionStatement> {
+    let mut rest = sql.trim().trim_end_matches(';').trim_end();
+
+    if keyword(&mut rest, "CREATE") {
+        let or_replace = keyword(&mut rest, "OR");
+        if or_replace && !keyword(&mut rest, "REPLACE") {
+            return None;
+        }
+        let _ = keyword(&mut rest, "TEMPORARY") || keyword(&mut rest, "TEMP");
+        let kind = table_kind(&mut rest)?;
+        let name = identifier(&mut rest)?;
+        if !keyword(&mut rest, "AS") || rest.is_empty() {
+            return None;
+        }
+        return Some(SessionStatement::Create {
+            name,
+            kind,
+            or_replace,
+            query: rest.to_string(),
+        });
+    }
+
+    if keyword(&mut rest, "DROP") {
+        table_kind(&mut rest)?;
+        let if_exists = keyword(&mut rest, "IF");
+        if if_exists && !keyword(&mut rest, "EXISTS") {
+            return None;
+        }
+        let name = identifier(&mut rest)?;
+        if !rest.is_empty() {
+            return None;
+        }
+        return Some(SessionStatement::Drop { name, if_exists });
+    }
+
+    None
+}
+
+fn table_kind(rest: &mut &str) -> Option<SessionTableKind> {
+    if keyword(rest, "VIEW") {
+        Some(SessionTableKind::View)
+    } else if keyword(rest, "TABLE") {
+        Some(SessionTableKind::Table)
+    } else {
+        None
+    }
+}
+
+/// Consume a keyword followed by whitespace or the end of the statement
+fn keyword(rest: &mut &str, word: &str) -> bool {
+    let matches = rest
+        .get(..word.len())
+        .is_some_and(|head| head.eq_ignore_ascii_case(word))
+        && rest[word.len()..]
+            .chars()
+            .next()
+            .is_none_or(char::is_whitespace);
+    if matches {
+        *rest = rest[word.len()..].trim_start();
+    }
+    matches
+}
+
+fn identifier(rest: &mut &str) -> Option<String> {
+    let (name, tail) = if let Some(quoted) = rest.strip_prefix('"') {
+        let end = quoted.find('"')?;
+        (quoted[..end].to_string(), &quoted[end + 1..])
+    } else {
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        (rest[..end].to_string(), &rest[end..])
+    };
+
+    if name.is_empty() {
+        return None;
+    }
+    *rest = tail.trim_start();
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create(sql: &str) -> Option<(String, SessionTableKind, bool, String)> {
+        match parse_session_statement(sql)? {
+            SessionStatement::Create {
+                name,
+                kind,
+                or_replace,
+                query,
+            } => Some((name, kind, or_replace, query)),
+            SessionStatement::Drop { .. } => None,
+        }
+    }
+
+    fn drop(sql: &str) -> Option<(String, bool)> {
+        match parse_session_statement(sql)? {
+            SessionStatement::Drop { name, if_exists } => Some((name, if_exists)),
+            SessionStatement::Create { .. } => None,
+        }
+    }
+
+    #[test]
+    fn create_statements_are_parsed() {
+        assert_eq!(
+            create("create or replace temp table big AS SELECT * FROM t;"),
+            Some((
+                "big".to_string(),
+                SessionTableKind::Table,
+                true,
+                "SELECT * FROM t".to_string()
+            ))
+        );
+        assert_eq!(
+            create("CREATE VIEW \"my view\" AS SELECT 1 ;  "),
+            Some((
+                "my view".to_string(),
+                SessionTableKind::View,
+                false,
+                "SELECT 1".to_string()
+            ))
+        );
+
+        assert!(create("CREATE OR VIEW x AS SELECT 1").is_none());
+        assert!(create("CREATE VIEW x SELECT 1").is_none());
+        assert!(create("CREATE VIEW x AS").is_none());
+        assert!(create("CREATE VIEWS x AS SELECT 1").is_none());
+        assert!(create("CREATE VIEW \"x AS SELECT 1").is_none());
+        assert!(parse_session_statement("SELECT * FROM views").is_none());
+    }
+
+    #[test]
+    fn drop_statements_are_parsed() {
+        assert_eq!(drop("DROP TABLE t;"), Some(("t".to_string(), false)));
+        assert_eq!(
+            drop("drop view if exists \"a b\""),
+            Some(("a b".to_string(), true))
+        );
+
+        assert!(drop("DROP TABLE t extra").is_none());
+        assert!(drop("DROP TABLE t; DROP TABLE u").is_none());
+        assert!(drop("DROP TABLE IF t").is_none());
+        assert!(drop("DROP t").is_none());
+    }
+
+    #[test]
+    fn views_that_cannot_be_planned_only_fail_the_queries_that_read_them() {
+        let mut session = SqlSession::default();
+        let df = df!("a" => [1i64, 2, 3]).unwrap();
+        session
+            .create("t", SessionTableKind::Table, false, "SELECT 1", Some(df))
+            .unwrap();
+        session
+            .create(
+                "stale",
+                SessionTableKind::View,
+                false,
+                "SELECT * FROM missing",
+                None,
+            )
+            .unwrap();
+        session
+            .create(
+                "over_stale",
+                SessionTableKind::View,
+                false,
+                "SELECT a FROM stale",
+                None,
+            )
+            .unwrap();
+        session
+            .create(
+                "small",
+                SessionTableKind::View,
+                false,
+                "SELECT a FROM t WHERE a < 3",
+                None,
+            )
+            .unwrap();
+
+        let mut ctx = SQLContext::new();
+        let broken = session.register(&mut ctx);
+        let names: Vec<_> = broken.iter().map(|view| view.name.as_str()).collect();
+        assert_eq!(names, ["stale", "over_stale"]);
+
+        let df = ctx
+            .execute("SELECT * FROM small")
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(df.height(), 2);
+
+        for query in ["SELECT * FROM stale", "SELECT * FROM \"over_stale\""] {
+            let error = ctx.execute(query).err().unwrap().to_string();
+            let error = broken_view_error(query, error, &broken);
+            assert!(error.starts_with("Failed to plan view stale:"), "{}", error);
+        }
+        let error = broken_view_error("SELECT * FROM other", "not found".to_string(), &broken);
+        assert_eq!(error, "not found");
+    }
+
+    #[test]
+    fn replaced_tables_are_left_out_of_the_table_bytes() {
+        let df = df!("a" => [1i64, 2, 3]).unwrap();
+        let size = df.estimated_size();
+        let mut session = SqlSession::default();
+        session
+            .create(
+                "a",
+                SessionTableKind::Table,
+                false,
+                "SELECT 1",
+                Some(df.clone()),
+            )
+            .unwrap();
+        session
+            .create("b", SessionTableKind::Table, false, "SELECT 1", Some(df))
+            .unwrap();
+        assert!(session
+            .create("b", SessionTableKind::View, false, "SELECT 1", None)
+            .is_err());
+
+        assert_eq!(session.table_bytes(None), 2 * size);
+        assert_eq!(session.table_bytes(Some("b")), size);
+    }
+}
@@ -25,6 +25,19 @@ export interface SortState {
     ascending: boolean
 }
 
+// The view on screen, as the backend identifies it for saving or copying it
+export type ViewRef =
+    | {kind: 'file'; filePath: string; sorting: SortState[] | null}
+    | {kind: 'query'; query: string; allFiles: string[]; tableNames: Record<string, string>}
+
+function sanitizeTableName(name: string): string {
+    let sanitized = name.replace(/[^A-Za-z0-9_]/g, '_')
+    if (/^[0-9]/.test(sanitized)) {
+        sanitized = '_' + sanitized
+    }
+    return sanitized || 'table'
+}
+
 export interface FileSession {
     id: string
     path: string
@@ -125,6 +138,25 @@ export const dataStore = {
             this.currentQuery.trim().length > 0
         )
     },
+    get currentView(): ViewRef | null {
+        const session = this.activeSession
+        if (!session) return null
+        if (this.isQueryMode && this.currentQuery) {
+            return {
+                kind: 'query',
+                query: this.currentQuery,
+                allFiles: sessions.map((s) => s.path),
+                tableNames: Object.fromEntries(
+                    sessions.map((s) => [s.path, sanitizeTableName(s.name)])
+                ),
+            }
+        }
+        return {
+            kind: 'file',
+            filePath: session.path,
+            sorting: session.sortStates.length > 0 ? session.sortStates : null,
+        }
+    },
     get baseColumns() {
         return this.activeSession?.baseColumns || null
     },
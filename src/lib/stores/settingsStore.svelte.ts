@@ -4,12 +4,14 @@ export interface Settings {
     theme: 'light' | 'dark'
     fontFamily: FontFamily
     fontSize: number
+    cacheMemoryMb: number
 }
 
 const DEFAULT_SETTINGS: Settings = {
     theme: 'dark',
     fontFamily: 'Iosevka',
     fontSize: 14,
+    cacheMemoryMb: 1024,
 }
 
 // Module-level state